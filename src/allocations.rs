@@ -0,0 +1,116 @@
+//! A global allocator that counts allocations and tracks the peak of live
+//! bytes, for the bench harness. It is opt-in: the `aoc` binary only installs
+//! it when built with the `count-allocations` feature.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use serde::{Deserialize, Serialize};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting as it goes. Install it with
+/// `#[global_allocator]`.
+pub struct Counting;
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            grow(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    /// Counts as a new allocation, as it usually moves the data.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+            grow(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn grow(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+/// What one step allocated. Other threads allocating at the same time are
+/// counted too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Allocations {
+    pub count: usize,
+    /// The most bytes live at once above what was live before the step.
+    pub peak_bytes: usize,
+}
+
+/// Whether [`Counting`] is the global allocator.
+pub fn is_counting() -> bool {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    drop(black_box(Box::new(0u8)));
+    ALLOCATIONS.load(Ordering::Relaxed) != before
+}
+
+/// Runs `f` and counts what it allocated, all zero unless [`Counting`] is
+/// installed.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Allocations) {
+    let live = LIVE.load(Ordering::Relaxed);
+    PEAK.store(live, Ordering::Relaxed);
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let result = f();
+    let allocations = Allocations {
+        count: ALLOCATIONS.load(Ordering::Relaxed) - before,
+        peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(live),
+    };
+    (result, allocations)
+}
+
+/// Only for the lib tests built with `count-allocations`, like the binary.
+#[cfg(all(test, feature = "count-allocations"))]
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+#[cfg(feature = "count-allocations")]
+#[test]
+pub fn test_measure() {
+    assert!(is_counting());
+    let (sum, allocations) = measure(|| {
+        let kept: Vec<u64> = black_box(Vec::with_capacity(1000));
+        let dropped: Vec<u8> = black_box(vec![1; 4000]);
+        drop(dropped);
+        kept.capacity()
+    });
+    assert_eq!(sum, 1000);
+    assert!(allocations.count >= 2);
+    assert!(allocations.peak_bytes >= 12000);
+}
+
+#[cfg(not(feature = "count-allocations"))]
+#[test]
+pub fn test_measure_without_counting() {
+    assert!(!is_counting());
+    let (_, allocations) = measure(|| black_box(vec![1u8; 4000]));
+    assert_eq!(allocations, Allocations::default());
+}
@@ -0,0 +1,323 @@
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use toml::{Table, Value};
+
+use crate::{
+    helper::{input_dir, input_name, Input},
+    solution::{Answer, Params, Part},
+    Day,
+};
+
+/// Lives next to the inputs, one table per input file stem. An input can also
+/// have a sidecar, `day12_test.toml` next to `day12_test.txt`, holding just
+/// `part1` and `part2`. Either can give the input's [`Params`] in a `params`
+/// table.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Known answers keyed by input file stem, `day12` or `day12_test`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Answers {
+    expected: HashMap<String, [Option<Answer>; 2]>,
+    params: HashMap<String, Params>,
+}
+
+impl Answers {
+    /// Loads `answers.toml` and every sidecar from the input directory.
+    pub fn load() -> Result<Answers> {
+        Answers::from_dir(&input_dir())
+    }
+
+    pub fn from_dir(dir: &Path) -> Result<Answers> {
+        let mut answers = match read_toml(&dir.join(ANSWERS_FILE))? {
+            Some(text) => Answers::parse(&text).with_context(|| format!("In {}", ANSWERS_FILE))?,
+            None => Answers::default(),
+        };
+
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(answers),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Could not read {}", dir.display()))
+            }
+        };
+        for entry in entries {
+            let path = entry?.path();
+            let (Some(stem), Some("toml")) = (
+                path.file_stem().and_then(|stem| stem.to_str()),
+                path.extension().and_then(|ext| ext.to_str()),
+            ) else {
+                continue;
+            };
+            if path.file_name() == Some(ANSWERS_FILE.as_ref()) {
+                continue;
+            }
+            if let Some(text) = read_toml(&path)? {
+                answers
+                    .insert(stem, &text.parse()?)
+                    .with_context(|| format!("In {}", path.display()))?;
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn parse(text: &str) -> Result<Answers> {
+        let table: Table = text.parse()?;
+        let mut answers = Answers::default();
+        for (input, parts) in &table {
+            let parts = parts
+                .as_table()
+                .ok_or_else(|| anyhow!("[{}] should be a table of part1 and part2", input))?;
+            answers.insert(input, parts)?;
+        }
+        Ok(answers)
+    }
+
+    fn insert(&mut self, input: &str, parts: &Table) -> Result<()> {
+        let mut expected = [None, None];
+        for (key, value) in parts {
+            if key == "params" {
+                let params = to_params(value).with_context(|| format!("[{}] params", input))?;
+                self.params.insert(input.to_owned(), params);
+                continue;
+            }
+            let slot = match key.as_str() {
+                "part1" => &mut expected[0],
+                "part2" => &mut expected[1],
+                _ => return Err(anyhow!("[{}] has unknown key {}", input, key)),
+            };
+            *slot = Some(to_answer(value).with_context(|| format!("[{}] {}", input, key))?);
+        }
+        self.expected.insert(input.to_owned(), expected);
+        Ok(())
+    }
+
+    pub fn get(&self, input: &str, part: Part) -> Option<&Answer> {
+        let index = match part {
+            Part::One => 0,
+            Part::Two => 1,
+        };
+        self.expected.get(input)?[index].as_ref()
+    }
+
+    /// The params to parse `input` with, none unless it lists some.
+    pub fn params(&self, input: &str) -> Params {
+        self.params.get(input).cloned().unwrap_or_default()
+    }
+}
+
+/// The params of the day's input or example, none for other inputs.
+pub fn params_for(input: &Input) -> Result<Params> {
+    match *input {
+        Input::Day { day, test } => Ok(Answers::load()?.params(&input_name(day, test))),
+        _ => Ok(Params::new()),
+    }
+}
+
+fn read_toml(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("Could not read {}", path.display())),
+    }
+}
+
+fn to_answer(value: &Value) -> Result<Answer> {
+    match value {
+        Value::Integer(number) => Ok(Answer::Number(*number)),
+        // Multi-line strings keep the newline after the closing line.
+        Value::String(text) => Ok(text.trim_end_matches('\n').parse()?),
+        _ => Err(anyhow!("expected an integer or a string, got {}", value)),
+    }
+}
+
+fn to_params(value: &Value) -> Result<Params> {
+    let table = value
+        .as_table()
+        .ok_or_else(|| anyhow!("expected a table, got {}", value))?;
+    table
+        .iter()
+        .try_fold(Params::new(), |params, (name, value)| {
+            let value = value
+                .as_integer()
+                .ok_or_else(|| anyhow!("expected an integer for {}, got {}", name, value))?;
+            Ok(params.with(name, value))
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail { expected: Answer },
+    Missing,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail { .. } => write!(f, "FAIL"),
+            Status::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Check {
+    pub part: Part,
+    pub answer: Answer,
+    pub status: Status,
+}
+
+/// Solves both parts of `day` and compares them with the known answers.
+pub fn verify(day: &Day, test: bool, answers: &Answers) -> Result<Vec<Check>> {
+    let name = input_name(day.number, test);
+    let input = day.solver.parse_with(
+        &Input::Day {
+            day: day.number,
+            test,
+        }
+        .load()?,
+        &answers.params(&name),
+    )?;
+    Part::ALL
+        .into_iter()
+        .map(|part| {
+            let answer = day.solver.solve(input.as_ref(), part)?;
+            let status = match answers.get(&name, part) {
+                None => Status::Missing,
+                Some(expected) if *expected == answer => Status::Pass,
+                Some(expected) => Status::Fail {
+                    expected: expected.clone(),
+                },
+            };
+            Ok(Check {
+                part,
+                answer,
+                status,
+            })
+        })
+        .collect()
+}
+
+#[test]
+pub fn test_parse_answers() {
+    let answers = Answers::parse(
+        "[day5]\npart1 = \"CMZ\"\n\n[day10]\npart1 = 13140\npart2 = \"\"\"\n#.\n.#\n\"\"\"\n",
+    )
+    .unwrap();
+    assert_eq!(answers.get("day5", Part::One), Some(&Answer::from("CMZ")));
+    assert_eq!(answers.get("day5", Part::Two), None);
+    assert_eq!(answers.get("day10", Part::One), Some(&Answer::from(13140)));
+    assert_eq!(
+        answers.get("day10", Part::Two),
+        Some(&Answer::from("#.\n.#"))
+    );
+    assert!(Answers::parse("[day1]\npart3 = 1\n").is_err());
+
+    let answers = Answers::parse("[day15_test]\npart1 = 26\nparams = { row = 10 }\n").unwrap();
+    assert_eq!(answers.params("day15_test"), Params::new().with("row", 10));
+    assert_eq!(answers.params("day15"), Params::new());
+    assert!(Answers::parse("[day15_test]\nparams = { row = \"ten\" }\n").is_err());
+}
+
+#[test]
+pub fn test_every_day_has_answers() {
+    let answers = Answers::load().unwrap();
+    for day in crate::DAYS {
+        for part in Part::ALL {
+            assert!(
+                answers.get(&input_name(day.number, false), part).is_some(),
+                "day {} part {} has no answer",
+                day.number,
+                part
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_verify() {
+    let answers = Answers::load().unwrap();
+    let day = crate::find_day(1).unwrap();
+    let checks = verify(day, false, &answers).unwrap();
+    assert!(checks.iter().all(|check| check.status == Status::Pass));
+
+    let checks = verify(day, false, &Answers::parse("[day1]\npart1 = 1\n").unwrap()).unwrap();
+    assert_eq!(
+        checks[0].status,
+        Status::Fail {
+            expected: Answer::from(1)
+        }
+    );
+    assert_eq!(checks[1].status, Status::Missing);
+}
+
+#[test]
+pub fn test_every_example_has_answers() {
+    let answers = Answers::load().unwrap();
+    for day in crate::DAYS {
+        for part in Part::ALL {
+            assert!(
+                answers.get(&input_name(day.number, true), part).is_some(),
+                "day {} part {} has no example answer",
+                day.number,
+                part
+            );
+        }
+    }
+}
+
+/// Checks one day and part against its example sidecar.
+#[cfg(test)]
+fn check_example(number: u8, part: Part) {
+    let day = crate::find_day(number).unwrap();
+    let check = verify(day, true, &Answers::load().unwrap())
+        .unwrap()
+        .into_iter()
+        .find(|check| check.part == part)
+        .unwrap();
+    assert_eq!(check.status, Status::Pass, "got {}", check.answer);
+}
+
+macro_rules! example_tests {
+    ($($name:ident: $day:expr),* $(,)?) => {
+        #[cfg(test)]
+        mod examples {
+            use super::check_example;
+            use crate::solution::Part;
+            $(
+                mod $name {
+                    use super::*;
+
+                    #[test]
+                    fn part1() {
+                        check_example($day, Part::One);
+                    }
+
+                    #[test]
+                    fn part2() {
+                        check_example($day, Part::Two);
+                    }
+                }
+            )*
+        }
+    };
+}
+
+example_tests! {
+    day1: 1,
+    day2: 2,
+    day3: 3,
+    day4: 4,
+    day5: 5,
+    day6: 6,
+    day8: 8,
+    day9: 9,
+    day10: 10,
+    day11: 11,
+    day12: 12,
+    day13: 13,
+    day14: 14,
+    day15: 15,
+}
@@ -0,0 +1,230 @@
+use std::{
+    fs,
+    hint::black_box,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    allocations::{self, Allocations},
+    solution::{Params, Part},
+    Day,
+};
+
+/// Fastest, median and slowest of repeated runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    #[serde(with = "nanos")]
+    pub min: Duration,
+    #[serde(with = "nanos")]
+    pub median: Duration,
+    #[serde(with = "nanos")]
+    pub max: Duration,
+    /// Counted in the last run, when the counting allocator is installed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<Allocations>,
+}
+
+impl Stats {
+    pub fn new(mut samples: Vec<Duration>) -> Stats {
+        assert!(!samples.is_empty(), "no samples");
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+            allocations: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: u8,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayBench {
+    pub fn steps(&self) -> [(&'static str, &Stats); 3] {
+        [
+            ("parse", &self.parse),
+            ("part 1", &self.part1),
+            ("part 2", &self.part2),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Report {
+    pub runs: usize,
+    pub example: bool,
+    pub days: Vec<DayBench>,
+}
+
+impl Report {
+    pub fn load(path: &Path) -> Result<Report> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Could not parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Could not write {}", path.display()))
+    }
+}
+
+/// Times parsing with `params` and both parts of `day` on `input`, `runs`
+/// times each. Allocations are counted too when the counting allocator is
+/// installed.
+pub fn bench_day(day: &Day, input: &str, params: &Params, runs: usize) -> Result<DayBench> {
+    let (mut parse, mut part1, mut part2) = (Vec::new(), Vec::new(), Vec::new());
+    let mut counted = [Allocations::default(); 3];
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let (parsed, allocations) =
+            allocations::measure(|| day.solver.parse_with(black_box(input), params));
+        parse.push(start.elapsed());
+        let parsed = parsed?;
+        counted[0] = allocations;
+
+        for (step, part, samples) in [(1, Part::One, &mut part1), (2, Part::Two, &mut part2)] {
+            let start = Instant::now();
+            let (answer, allocations) =
+                allocations::measure(|| day.solver.solve(parsed.as_ref(), part));
+            samples.push(start.elapsed());
+            black_box(answer?);
+            counted[step] = allocations;
+        }
+    }
+
+    let counting = allocations::is_counting();
+    let stats = |samples, allocations| Stats {
+        allocations: counting.then_some(allocations),
+        ..Stats::new(samples)
+    };
+    Ok(DayBench {
+        day: day.number,
+        parse: stats(parse, counted[0]),
+        part1: stats(part1, counted[1]),
+        part2: stats(part2, counted[2]),
+    })
+}
+
+/// A step whose median got slower than the baseline allows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub step: &'static str,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl Regression {
+    /// How much slower, in percent of the baseline.
+    pub fn slowdown(&self) -> f64 {
+        (self.current.as_secs_f64() / self.baseline.as_secs_f64() - 1.0) * 100.0
+    }
+}
+
+/// Compares medians, flagging steps more than `threshold` percent slower.
+/// Days missing from the baseline are skipped.
+pub fn compare(current: &Report, baseline: &Report, threshold: f64) -> Vec<Regression> {
+    let mut regressions = Vec::new();
+    for day in &current.days {
+        let Some(old) = baseline.days.iter().find(|old| old.day == day.day) else {
+            continue;
+        };
+        for ((step, stats), (_, old_stats)) in day.steps().into_iter().zip(old.steps()) {
+            let allowed = old_stats.median.as_secs_f64() * (1.0 + threshold / 100.0);
+            if stats.median.as_secs_f64() > allowed {
+                regressions.push(Regression {
+                    day: day.day,
+                    step,
+                    baseline: old_stats.median,
+                    current: stats.median,
+                });
+            }
+        }
+    }
+    regressions
+}
+
+/// Durations as whole nanoseconds in the JSON report.
+mod nanos {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_nanos().try_into().unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_nanos(u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+fn report(medians: &[(u8, u64)]) -> Report {
+    let stats = |median| Stats::new(vec![Duration::from_micros(median)]);
+    Report {
+        runs: 1,
+        example: false,
+        days: medians
+            .iter()
+            .map(|&(day, median)| DayBench {
+                day,
+                parse: stats(10),
+                part1: stats(median),
+                part2: stats(10),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+pub fn test_stats() {
+    let stats = Stats::new([5, 1, 3, 9, 2].map(Duration::from_millis).to_vec());
+    assert_eq!(stats.min, Duration::from_millis(1));
+    assert_eq!(stats.median, Duration::from_millis(3));
+    assert_eq!(stats.max, Duration::from_millis(9));
+}
+
+#[test]
+pub fn test_compare() {
+    let baseline = report(&[(1, 100), (2, 100)]);
+    let current = report(&[(1, 105), (2, 150), (3, 1000)]);
+    let regressions = compare(&current, &baseline, 10.0);
+    assert_eq!(regressions.len(), 1);
+    assert_eq!((regressions[0].day, regressions[0].step), (2, "part 1"));
+    assert_eq!(regressions[0].slowdown().round(), 50.0);
+}
+
+#[test]
+pub fn test_report_json() {
+    let report = report(&[(1, 100)]);
+    let json = serde_json::to_string(&report).unwrap();
+    assert!(json.contains("\"median\":100000"));
+    assert_eq!(serde_json::from_str::<Report>(&json).unwrap(), report);
+}
+
+#[test]
+pub fn test_bench_day() {
+    let day = crate::find_day(1).unwrap();
+    let input = crate::helper::read_string(1, true).unwrap();
+    let bench = bench_day(day, &input, &Params::new(), 3).unwrap();
+    assert_eq!(bench.day, 1);
+    assert!(bench.part1.min <= bench.part1.median && bench.part1.median <= bench.part1.max);
+    if cfg!(feature = "count-allocations") {
+        let parse = bench.parse.allocations.unwrap();
+        assert!(parse.count > 0 && parse.peak_bytes > 0);
+    } else {
+        assert_eq!(bench.parse.allocations, None);
+    }
+}
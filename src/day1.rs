@@ -1,65 +1,81 @@
-use std::cmp;
+use anyhow::Result;
 
-use crate::helper::read_lines;
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::ParseError,
+    solution::{solution_api, Answer, Solution},
+};
 
-#[test]
-pub fn test_day1_pt_1() {
-    let mut elves: Vec<usize> = Vec::new();
-    let report: Vec<String> = read_lines(1, false);
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut elves: Vec<usize> = Vec::new();
 
-    let mut sum = 0;
-    for calorie in report {
-        if calorie == "" {
+        let mut sum = 0;
+        for (i, calorie) in input.lines().enumerate() {
+            if calorie.is_empty() {
+                elves.push(sum);
+                sum = 0;
+            } else {
+                sum += calorie.parse::<usize>().map_err(|_| {
+                    ParseError::new("expected calories", calorie)
+                        .at_line(i + 1)
+                        .for_day(1)
+                })?;
+            }
+        }
+        if sum > 0 {
             elves.push(sum);
-            sum = 0;
-        } else {
-            sum = sum + calorie.parse::<usize>().unwrap();
         }
-    }
-    if sum > 0 {
-        elves.push(sum);
+        Ok(elves)
     }
 
-    let max_value = elves.iter().fold(0, |current_max, elem| {
-        cmp::max(current_max, elem.to_owned())
-    });
+    fn part1(elves: &Self::Input) -> Result<Answer> {
+        let max_value = elves
+            .iter()
+            .fold(0, |current_max, elem| current_max.max(elem.to_owned()));
+        Ok(max_value.into())
+    }
 
-    println!("Part1 Max number is {:?}", max_value);
-    assert!(false);
+    fn part2(elves: &Self::Input) -> Result<Answer> {
+        let max_value: usize = elves
+            .iter()
+            .fold([&0, &0, &0], |current_max, elem| {
+                if elem > current_max[0] {
+                    let mut new_max = [elem, current_max[1], current_max[2]];
+                    new_max.sort();
+                    new_max
+                } else {
+                    current_max
+                }
+            })
+            .into_iter()
+            .sum();
+        Ok(max_value.into())
+    }
 }
 
-#[test]
-pub fn test_day1_pt_2() {
-    let mut elves: Vec<usize> = Vec::new();
-    let report: Vec<String> = read_lines(1, false);
+solution_api!(Day1);
 
-    let mut sum = 0;
-    for calorie in report {
-        if calorie == "" {
-            elves.push(sum);
-            sum = 0;
-        } else {
-            sum = sum + calorie.parse::<usize>().unwrap();
-        }
-    }
-    if sum > 0 {
-        elves.push(sum);
-    }
+#[test]
+pub fn test_day1_pt_1() {
+    let elves = Day1::parse(&read_string(1, false).unwrap()).unwrap();
+    assert_eq!(Day1::part1(&elves).unwrap(), Answer::from(69883));
+}
 
-    let max_value: usize = elves
-        .iter()
-        .fold([&0, &0, &0], |current_max, elem| {
-            if elem > current_max[0] {
-                let mut new_max = [elem, &current_max[1], &current_max[2]];
-                new_max.sort();
-                new_max
-            } else {
-                current_max
-            }
-        })
-        .into_iter()
-        .sum();
+#[test]
+pub fn test_day1_parse_error() {
+    let err = Day1::parse("1000\n\n2OOO\n").unwrap_err();
+    let err = err.downcast::<ParseError>().unwrap();
+    assert_eq!((err.day, err.line), (Some(1), Some(3)));
+}
 
-    println!("Part2 Max number is {:?}", max_value);
-    assert!(false);
+#[test]
+pub fn test_day1_pt_2() {
+    let elves = Day1::parse(&read_string(1, false).unwrap()).unwrap();
+    assert_eq!(Day1::part2(&elves).unwrap(), Answer::from(207576));
 }
@@ -1,17 +1,24 @@
-use std::{collections::HashSet, error::Error, hash::Hash, str::FromStr};
-
-use nom::{
-    branch::alt, bytes::complete::tag, character::complete, sequence::preceded, IResult, Parser,
+use anyhow::{anyhow, Result};
+use nom::{branch::alt, bytes::complete::tag, sequence::preceded, Parser};
+
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::{parse_lines, ParseError},
+    grid::Grid,
+    parse::{parse_all, signed, IResult},
+    solution::{solution_api, Answer, Part, Solution},
+    trace,
+    visual::{record, Frame, Recorder},
 };
 
-use crate::helper::{read_lines, ParseError};
-
 #[derive(Debug, Clone, PartialEq)]
-enum Instruction {
+pub enum Instruction {
     Add { x: i32 },
     NoOp,
 }
-struct CPU {
+#[derive(Debug)]
+struct Cpu {
     cycle: usize,
     register: i32,
     instructions: Vec<Instruction>,
@@ -19,9 +26,9 @@ struct CPU {
     current_instruction: Option<Instruction>,
 }
 
-impl CPU {
-    pub fn new(instructions: Vec<Instruction>) -> CPU {
-        CPU {
+impl Cpu {
+    pub fn new(instructions: Vec<Instruction>) -> Cpu {
+        Cpu {
             cycle: 0,
             register: 1,
             instructions,
@@ -31,7 +38,7 @@ impl CPU {
     }
 }
 
-impl Iterator for CPU {
+impl Iterator for Cpu {
     // We can refer to this type using Self::Item
     type Item = i32;
 
@@ -44,24 +51,29 @@ impl Iterator for CPU {
     fn next(&mut self) -> Option<Self::Item> {
         if let Some(Instruction::Add { x }) = self.current_instruction {
             self.current_instruction = None;
-            self.cycle = self.cycle + 1;
-            self.register = self.register + x;
-            self.current_instruction_index = self.current_instruction_index + 1;
-            // println!("Cycle: {} Register: {}", self.cycle, self.register - x);
+            self.cycle += 1;
+            self.register += x;
+            self.current_instruction_index += 1;
+            trace::event!(
+                Trace,
+                "cycle",
+                cycle = self.cycle,
+                register = self.register - x
+            );
             Some(self.register - x)
         } else if self.current_instruction_index < self.instructions.len() {
             match self.instructions[self.current_instruction_index] {
-                Instruction::Add { x } => {
+                Instruction::Add { .. } => {
                     self.current_instruction =
                         Some(self.instructions[self.current_instruction_index].clone());
-                    self.cycle = self.cycle + 1;
-                    // println!("Cycle: {} Register: {}", self.cycle, self.register);
+                    self.cycle += 1;
+                    trace::event!(Trace, "cycle", cycle = self.cycle, register = self.register);
                     Some(self.register)
                 }
                 Instruction::NoOp => {
-                    self.cycle = self.cycle + 1;
-                    self.current_instruction_index = self.current_instruction_index + 1;
-                    // println!("Cycle: {} Register: {}", self.cycle, self.register);
+                    self.cycle += 1;
+                    self.current_instruction_index += 1;
+                    trace::event!(Trace, "cycle", cycle = self.cycle, register = self.register);
                     Some(self.register)
                 }
             }
@@ -71,24 +83,76 @@ impl Iterator for CPU {
     }
 }
 
+pub struct Day10;
+
+/// Both parts read the register for 240 cycles, the six rows of the CRT.
+const CYCLES: usize = 240;
+
+fn too_short() -> anyhow::Error {
+    anyhow!("day 10: the program runs for fewer than {} cycles", CYCLES)
+}
+
+impl Solution for Day10 {
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let cpu = parse_cpu(input).map_err(|err| err.for_day(10))?;
+        check_cycles(input, &cpu.instructions).map_err(|err| err.for_day(10))?;
+        Ok(cpu.instructions)
+    }
+
+    fn part1(instructions: &Self::Input) -> Result<Answer> {
+        let mut cpu = Cpu::new(instructions.clone());
+
+        let signals = [
+            (20, cpu.nth(19).ok_or_else(too_short)?),
+            (60, cpu.nth(39).ok_or_else(too_short)?),
+            (100, cpu.nth(39).ok_or_else(too_short)?),
+            (140, cpu.nth(39).ok_or_else(too_short)?),
+            (180, cpu.nth(39).ok_or_else(too_short)?),
+            (220, cpu.nth(39).ok_or_else(too_short)?),
+        ];
+
+        Ok(signals
+            .iter()
+            .fold(0, |acc, signal| acc + signal.0 * signal.1)
+            .into())
+    }
+
+    fn part2(instructions: &Self::Input) -> Result<Answer> {
+        Ok(draw_crt(instructions, &mut ())?.to_string().into())
+    }
+}
+
+solution_api!(Day10);
+
+/// Frames of the CRT after every pixel, for `aoc record`. Both parts run the
+/// same program, so they record the same picture.
+pub fn simulate(input: &str, _part: Part, recorder: &mut dyn Recorder) -> Result<()> {
+    draw_crt(&Day10::parse(input)?, recorder)?;
+    Ok(())
+}
+
+/// Lights a pixel when the sprite at the register covers the column being drawn.
+fn draw_crt(instructions: &[Instruction], recorder: &mut dyn Recorder) -> Result<Frame> {
+    let mut cpu = Cpu::new(instructions.to_vec());
+    let mut crt = Grid::new(40, 6, ' ');
+    for pos in crt.positions() {
+        let register = cpu.next().ok_or_else(too_short)?;
+        crt[pos] = if (pos.0 as i32).abs_diff(register) < 2 {
+            '#'
+        } else {
+            '.'
+        };
+        record(recorder, || (crt.clone(), pos));
+    }
+    Ok(crt)
+}
+
 #[test]
 pub fn test_day10_pt_1() {
-    let lines: Vec<String> = read_lines(10, false);
-    let (_, mut cpu) = parse_CPU(lines).unwrap();
-
-    let mut signals: Vec<(i32, i32)> = Vec::new();
-    signals.push((20, cpu.nth(19).unwrap()));
-    signals.push((60, cpu.nth(39).unwrap()));
-    signals.push((100, cpu.nth(39).unwrap()));
-    signals.push((140, cpu.nth(39).unwrap()));
-    signals.push((180, cpu.nth(39).unwrap()));
-    signals.push((220, cpu.nth(39).unwrap()));
-
-    let sum = signals
-        .iter()
-        .fold(0, |acc, signal| acc + signal.0 * signal.1);
-    println!("Part1 score is {:?} with sum {}", signals, sum);
-    assert!(false);
+    let instructions = Day10::parse(&read_string(10, false).unwrap()).unwrap();
+    assert_eq!(Day10::part1(&instructions).unwrap(), Answer::from(17380));
 }
 
 #[test]
@@ -96,7 +160,6 @@ pub fn test_parse_instruction() {
     let input = r#"noop
 	addx 3
 	addx -5"#;
-    let lines = input.split("\n").map(|l| l.to_owned()).collect();
 
     let expected = vec![
         Instruction::NoOp,
@@ -104,45 +167,80 @@ pub fn test_parse_instruction() {
         Instruction::Add { x: -5 },
     ];
 
-    let (_, result) = parse_CPU(lines).unwrap();
+    let result = parse_cpu(input).unwrap();
     assert_eq!(expected, result.instructions);
 }
 
 #[test]
 pub fn test_day10_pt_2() {
-    let lines: Vec<String> = read_lines(10, false);
-    let (_, mut cpu) = parse_CPU(lines).unwrap();
-
-    let mut crt = vec![vec![' '; 40]; 6];
-    for row in 0..6 {
-        for col in 0..40 {
-            let register = cpu.next().unwrap();
-            if (col as i32).abs_diff(register) < 2 {
-                crt[row][col] = '#';
-            } else {
-                crt[row][col] = '.';
-            }
-        }
-    }
+    let instructions = Day10::parse(&read_string(10, false).unwrap()).unwrap();
+    let crt = [
+        "####..##...##..#..#.####.###..####..##..",
+        "#....#..#.#..#.#..#....#.#..#.#....#..#.",
+        "###..#....#....#..#...#..#..#.###..#....",
+        "#....#.##.#....#..#..#...###..#....#....",
+        "#....#..#.#..#.#..#.#....#.#..#....#..#.",
+        "#.....###..##...##..####.#..#.####..##..",
+    ];
+    assert_eq!(
+        Day10::part2(&instructions).unwrap(),
+        Answer::from(crt.join("\n"))
+    );
+}
+
+fn parse_cpu(input: &str) -> Result<Cpu, ParseError> {
+    let instructions = parse_lines(10, input, |line| {
+        let trimmed = line.trim();
+        parse_all(trimmed, parse_instruction)
+            .map_err(|err| err.shifted(line.len() - line.trim_start().len()))
+    })?;
+    Ok(Cpu::new(instructions))
+}
 
-    for row in 0..6 {
-        println!("{}", String::from_iter(&crt[row]));
+fn check_cycles(input: &str, instructions: &[Instruction]) -> Result<(), ParseError> {
+    let cycles: usize = instructions
+        .iter()
+        .map(|instruction| match instruction {
+            Instruction::Add { .. } => 2,
+            Instruction::NoOp => 1,
+        })
+        .sum();
+    if cycles < CYCLES {
+        let lines = input.lines().count();
+        return Err(ParseError::new(
+            &format!(
+                "expected a program of at least {} cycles, got {}",
+                CYCLES, cycles
+            ),
+            input.lines().last().unwrap_or(""),
+        )
+        .at_line(lines.max(1)));
     }
-    assert!(false);
+    Ok(())
 }
 
-fn parse_CPU(lines: Vec<String>) -> IResult<String, CPU> {
-    let instructions = lines
-        .into_iter()
-        .map(|l| parse_instruction(l.as_str()).unwrap().1)
-        .collect();
-    let mut cpu = CPU::new(instructions);
-    Ok(("".to_owned(), cpu))
+#[test]
+pub fn test_check_cycles() {
+    let err = Day10::parse("noop\n").unwrap_err().to_string();
+    assert_eq!(
+        err,
+        "day 10, line 1: expected a program of at least 240 cycles, got 1: \"noop\""
+    );
+    assert!(Day10::parse(&"addx 1\n".repeat(120)).is_ok());
+    // Programs built without parse are not checked up front.
+    assert!(Day10::part1(&vec![Instruction::NoOp]).is_err());
+    assert!(Day10::part2(&Vec::new()).is_err());
+}
+
+#[test]
+pub fn test_parse_cpu_error() {
+    let err = parse_cpu("noop\naddx three\n").unwrap_err();
+    assert_eq!((err.line, err.column), (Some(2), Some(6)));
 }
 
-fn parse_instruction(input: &str) -> IResult<&str, Instruction> {
+fn parse_instruction(input: &str) -> IResult<'_, Instruction> {
     alt((
         tag("noop").map(|_| Instruction::NoOp),
-        preceded(tag("addx"), complete::i32).map(|num| Instruction::Add { x: num }),
+        preceded(tag("addx "), signed).map(|num| Instruction::Add { x: num }),
     ))(input)
 }
@@ -1,74 +1,130 @@
-mod monkey;
+pub mod monkey;
 
-use num::{self, integer};
+use anyhow::Result;
+use num::integer;
 
+#[cfg(test)]
+use crate::helper::read_string;
 use crate::{
-    day11::monkey::{parse_monkeys, Arithmetic, Monkey, Operation, Value},
-    helper::read_string,
+    day11::monkey::{parse_monkey, Monkey},
+    error::ParseError,
+    parse::{blocks, parse_all},
+    solution::{solution_api, Answer, Solution},
 };
 
-#[test]
-pub fn test_day11_pt_1() {
-    let input: String = read_string(11, false);
-    let (_, mut monkeys) = parse_monkeys(&input).unwrap();
+pub struct Day11;
 
-    let size = monkeys.len();
-    for _ in 0..20 {
-        for i in 0..size {
-            let throws = monkeys[i].inspect_and_throw(&|num| num / 3);
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
 
-            for (destination, item) in throws {
-                monkeys[destination as usize].starting_items.push(item);
+    fn parse(input: &str) -> Result<Self::Input> {
+        let monkeys = parse_all(input, blocks(parse_monkey)).map_err(|err| err.for_day(11))?;
+        // Monkey business multiplies the two busiest monkeys.
+        if monkeys.len() < 2 {
+            return Err(ParseError::new("expected at least two monkeys", "")
+                .for_day(11)
+                .into());
+        }
+        for (i, monkey) in monkeys.iter().enumerate() {
+            if monkey.divisible_test == 0 {
+                return Err(ParseError::new("expected a divisor above 0", "0")
+                    .for_day(11)
+                    .into());
+            }
+            for target in [monkey.monkey_true, monkey.monkey_false] {
+                if target as usize >= monkeys.len() {
+                    let text = format!("Monkey {} throws to monkey {}", i, target);
+                    return Err(ParseError::new("expected an existing monkey", &text)
+                        .for_day(11)
+                        .into());
+                }
             }
         }
+        Ok(monkeys)
     }
 
-    let mut inspected = monkeys.iter().map(|m| m.inspected).collect::<Vec<u64>>();
-    inspected.sort_by(|a, b| b.cmp(a));
-    let monkey_business = inspected[0] * inspected[1];
-    println!("Part1 score is {:?}", monkey_business);
-    assert_eq!(monkey_business, 51075);
-}
+    fn part1(monkeys: &Self::Input) -> Result<Answer> {
+        let mut monkeys = monkeys.clone();
 
-#[test]
-pub fn test_day11_pt_2() {
-    let input: String = read_string(11, false);
-    let (_, mut monkeys) = parse_monkeys(&input).unwrap();
+        let size = monkeys.len();
+        for _ in 0..20 {
+            for i in 0..size {
+                let throws = monkeys[i].inspect_and_throw(&|num| num / 3);
 
-    let lcm = find_lcm(&monkeys.iter().map(|m| m.divisible_test).collect());
+                for (destination, item) in throws {
+                    monkeys[destination as usize].starting_items.push(item);
+                }
+            }
+        }
 
-    println!("Worries LCM: {}", lcm);
+        Ok(monkey_business(&monkeys).into())
+    }
 
-    let size = monkeys.len();
-    for _ in 0..10000 {
-        for i in 0..size {
-            let throws = monkeys[i].inspect_and_throw(&create_worry_manager(lcm));
+    fn part2(monkeys: &Self::Input) -> Result<Answer> {
+        let mut monkeys = monkeys.clone();
 
-            for (destination, item) in throws {
-                monkeys[destination as usize].starting_items.push(item);
+        let lcm = find_lcm(
+            &monkeys
+                .iter()
+                .map(|m| m.divisible_test)
+                .collect::<Vec<u64>>(),
+        );
+
+        let size = monkeys.len();
+        for _ in 0..10000 {
+            for i in 0..size {
+                let throws = monkeys[i].inspect_and_throw(&create_worry_manager(lcm));
+
+                for (destination, item) in throws {
+                    monkeys[destination as usize].starting_items.push(item);
+                }
             }
         }
+
+        Ok(monkey_business(&monkeys).into())
     }
-    for (i, monkey) in monkeys.iter().enumerate() {
-        println!("Monkey {}: {:?}", i + 1, monkey);
-    }
+}
+
+solution_api!(Day11);
+
+#[test]
+pub fn test_day11_pt_1() {
+    let monkeys = Day11::parse(&read_string(11, false).unwrap()).unwrap();
+    assert_eq!(Day11::part1(&monkeys).unwrap(), Answer::from(51075));
+}
 
+#[test]
+pub fn test_day11_pt_2() {
+    let monkeys = Day11::parse(&read_string(11, false).unwrap()).unwrap();
+    assert_eq!(
+        Day11::part2(&monkeys).unwrap(),
+        Answer::from(11741456163u64)
+    );
+}
+
+fn monkey_business(monkeys: &[Monkey]) -> u64 {
     let mut inspected = monkeys.iter().map(|m| m.inspected).collect::<Vec<u64>>();
     inspected.sort_by(|a, b| b.cmp(a));
-    let monkey_business = inspected[0] as u64 * inspected[1] as u64;
-    println!("Part2 score is {:?}", monkey_business);
-    assert_eq!(monkey_business, 11741456163);
+    inspected[0] * inspected[1]
 }
 
 fn create_worry_manager(lcm: u64) -> impl Fn(u64) -> u64 {
     move |num: u64| num % lcm
 }
 
-fn find_lcm(worries: &Vec<u64>) -> u64 {
+fn find_lcm(worries: &[u64]) -> u64 {
     let res = worries
         .iter()
         .map(|num| *num as u128)
-        .reduce(|start: u128, elem| integer::lcm(start.into(), elem.into()))
+        .reduce(integer::lcm)
         .unwrap();
     res as u64
 }
+
+#[test]
+pub fn test_single_monkey() {
+    let example = read_string(11, true).unwrap();
+    let first = example.split("\n\n").next().unwrap();
+    let err = Day11::parse(first).unwrap_err();
+    assert_eq!(err.to_string(), "day 11: expected at least two monkeys");
+}
@@ -1,14 +1,15 @@
 use nom::{
     branch::alt,
     bytes::complete::{tag, take_while},
-    character::complete::{self, digit1, newline, one_of},
-    combinator::map_res,
+    character::complete::{char, newline},
     multi::separated_list1,
     sequence::{delimited, pair, preceded, tuple},
-    IResult, Parser,
+    Parser,
 };
 
-#[derive(PartialEq, Debug)]
+use crate::parse::{signed, unsigned, IResult};
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum Arithmetic {
     Add,
     Subtract,
@@ -16,7 +17,7 @@ pub enum Arithmetic {
     Divide,
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Value {
     Value { x: i32 },
     Old,
@@ -30,7 +31,7 @@ impl Value {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct Operation {
     pub lhs: Value,
     pub op: Arithmetic,
@@ -49,7 +50,7 @@ impl Operation {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct Monkey {
     pub starting_items: Vec<u64>,
     pub operation: Operation,
@@ -67,32 +68,32 @@ impl Monkey {
         for item in self.starting_items.iter() {
             let new_worry = self.operation.evaluate(*item);
             let new_worry = worry_manager(new_worry);
-            if new_worry % self.divisible_test == 0 {
+            if new_worry.is_multiple_of(self.divisible_test) {
                 throws.push((self.monkey_true, new_worry));
             } else {
                 throws.push((self.monkey_false, new_worry));
             }
         }
         self.starting_items = Vec::new();
-        self.inspected = self.inspected + throws.len() as u64;
+        self.inspected += throws.len() as u64;
         throws
     }
 }
 
-fn parse_monkey(input: &str) -> IResult<&str, Monkey> {
+pub fn parse_monkey(input: &str) -> IResult<'_, Monkey> {
     let (input, (starting_items, operation, divisible_test, monkey_true, monkey_false)) =
         preceded(
             pair(take_while(|c| c != '\n'), newline),
             tuple((
                 delimited(
                     tag("  Starting items: "),
-                    separated_list1(tag(", "), map_res(digit1, |s: &str| s.parse::<u64>())),
+                    separated_list1(tag(", "), unsigned),
                     newline,
                 ),
                 delimited(tag("  Operation: new = "), parse_operation, newline),
-                delimited(tag("  Test: divisible by "), complete::u64, newline),
-                delimited(tag("    If true: throw to monkey "), complete::u64, newline),
-                preceded(tag("    If false: throw to monkey "), complete::u64),
+                delimited(tag("  Test: divisible by "), unsigned, newline),
+                delimited(tag("    If true: throw to monkey "), unsigned, newline),
+                preceded(tag("    If false: throw to monkey "), unsigned),
             )),
         )(input)?;
 
@@ -109,18 +110,17 @@ fn parse_monkey(input: &str) -> IResult<&str, Monkey> {
     ))
 }
 
-fn parse_operation(input: &str) -> IResult<&str, Operation> {
+fn parse_operation(input: &str) -> IResult<'_, Operation> {
     let (input, (lhs, op, rhs)) = tuple((
         parse_operand,
         delimited(
             tag(" "),
-            one_of("+-*/").map(|op| match op {
-                '+' => Arithmetic::Add,
-                '-' => Arithmetic::Subtract,
-                '*' => Arithmetic::Multiply,
-                '/' => Arithmetic::Divide,
-                _ => panic!("Could not parse token"),
-            }),
+            alt((
+                char('+').map(|_| Arithmetic::Add),
+                char('-').map(|_| Arithmetic::Subtract),
+                char('*').map(|_| Arithmetic::Multiply),
+                char('/').map(|_| Arithmetic::Divide),
+            )),
             tag(" "),
         ),
         parse_operand,
@@ -129,17 +129,13 @@ fn parse_operation(input: &str) -> IResult<&str, Operation> {
     Ok((input, Operation { lhs, op, rhs }))
 }
 
-fn parse_operand(input: &str) -> IResult<&str, Value> {
+fn parse_operand(input: &str) -> IResult<'_, Value> {
     alt((
         tag("old").map(|_| Value::Old),
-        complete::i32.map(|num: i32| Value::Value { x: num }),
+        signed.map(|num: i32| Value::Value { x: num }),
     ))(input)
 }
 
-pub fn parse_monkeys(input: &str) -> IResult<&str, Vec<Monkey>> {
-    separated_list1(tag("\n\n"), parse_monkey)(input)
-}
-
 #[test]
 pub fn test_parse_monkey() {
     let monkey_input = r#"Monkey 0:
@@ -1,139 +1,184 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    hash::Hash,
-    str::FromStr,
-};
+use std::collections::VecDeque;
 
-use crate::helper::{read_lines, ParseError};
+use anyhow::{anyhow, Result};
 
-type Grid = Vec<Vec<char>>;
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::ParseError,
+    grid::{Grid, Pos},
+    search::{bfs, Found},
+    solution::{solution_api, Answer, Params, Part, Solution},
+    trace,
+    visual::{record, Recorder},
+};
 
-#[derive(Debug, Clone, Eq, Hash, PartialEq)]
-struct Position {
-    x: i32,
-    y: i32,
-}
+pub struct Day12;
 
-#[test]
-pub fn test_day12_pt_1() {
-    day12_pt_1();
-    assert!(false);
+fn unreachable() -> anyhow::Error {
+    anyhow!("day 12: E cannot be reached")
 }
 
-pub fn day12_pt_1() {
-    let lines: Vec<String> = read_lines(12, false);
-    let (grid, start, destination) = extract_grid(lines);
-    let steps = shortest_path(&grid, start, &destination).unwrap();
-    println!("Part1 score is {:?}", steps);
-}
+impl Solution for Day12 {
+    type Input = (Grid<char>, Pos, Pos);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut grid = Grid::parse(input, "expected an elevation a-z, S or E", |c| {
+            (c.is_ascii_lowercase() || c == 'S' || c == 'E').then_some(c)
+        })
+        .map_err(|err| err.for_day(12))?;
+        let mut markers = Vec::new();
+        for (marker, elevation) in [('S', 'a'), ('E', 'z')] {
+            if grid.iter().filter(|(_, &square)| square == marker).count() != 1 {
+                let message = format!("expected exactly one {}", marker);
+                return Err(ParseError::new(&message, "").for_day(12).into());
+            }
+            let pos = grid.find(&marker).unwrap();
+            grid[pos] = elevation;
+            markers.push(pos);
+        }
+        trace::event!(Debug, "markers", start = markers[0], elves = markers[1]);
+        // Every path from S is a path from an `a` for part 2 as well.
+        if shortest_path(&grid, [markers[0]], markers[1]).is_none() {
+            return Err(ParseError::new("expected E to be reachable from S", "")
+                .for_day(12)
+                .into());
+        }
+        Ok((grid, markers[0], markers[1]))
+    }
 
-fn extract_grid(lines: Vec<String>) -> (Vec<Vec<char>>, Position, Position) {
-    let mut grid: Grid = lines.iter().map(|l| l.chars().collect()).collect();
-    let start = find_start(&grid);
-    let destination = find_elves(&grid);
-    grid[start.y as usize][start.x as usize] = 'a';
-    grid[destination.y as usize][destination.x as usize] = 'z';
-    (grid, start, destination)
+    fn part1((grid, start, destination): &Self::Input) -> Result<Answer> {
+        Ok(shortest_path(grid, [*start], *destination)
+            .ok_or_else(unreachable)?
+            .cost
+            .into())
+    }
+
+    fn part2((grid, _, destination): &Self::Input) -> Result<Answer> {
+        Ok(shortest_path(grid, lowest(grid), *destination)
+            .ok_or_else(unreachable)?
+            .cost
+            .into())
+    }
 }
 
-fn shortest_path(grid: &Grid, start: Position, destination: &Position) -> Option<i32> {
-    println!("Start: {:?}", start);
-    println!("Elves: {:?}", destination);
+solution_api!(Day12);
 
-    let mut queue = VecDeque::new();
-    queue.push_back(((start.x as usize, start.y as usize), 0));
-    let mut visited = HashSet::<Position>::new();
-    while let Some(((x, y), len)) = queue.pop_front() {
-        if (y, x) == (destination.y as usize, destination.x as usize) {
-            return Some(len);
-        }
-        for (dx, dy) in [(0, -1), (-1, 0), (0, 1), (1, 0)] {
-            let (nx, ny) = ((x as isize + dx) as usize, (y as isize + dy) as usize);
-            if let Some(&square) = grid.get(ny).and_then(|row| row.get(nx)) {
-                if grid[y][x] as u32 + 1 >= square as u32
-                    && !visited.contains(&Position {
-                        x: nx as i32,
-                        y: ny as i32,
-                    })
-                {
-                    visited.insert(Position {
-                        x: nx as i32,
-                        y: ny as i32,
-                    });
+fn lowest(grid: &Grid<char>) -> impl Iterator<Item = Pos> + '_ {
+    grid.iter()
+        .filter(|(_, &square)| square == 'a')
+        .map(|(pos, _)| pos)
+}
 
-                    queue.push_back(((nx, ny), len + 1));
-                }
-            }
-        }
+/// The elevation map with the shortest path drawn one step per frame, for
+/// `aoc record`.
+pub fn simulate(input: &str, part: Part, recorder: &mut dyn Recorder) -> Result<()> {
+    let (grid, start, destination) = Day12::parse(input)?;
+    let path = match part {
+        Part::One => shortest_path(&grid, [start], destination),
+        Part::Two => shortest_path(&grid, lowest(&grid), destination),
     }
-    None
-}
+    .ok_or_else(unreachable)?
+    .path;
 
-fn print_grid(grid: &Vec<Vec<char>>, visited: &HashSet<Position>, elevation: char) {
-    for row in 0..grid.len() {
-        let mut line = " ".repeat(grid[0].len());
-        for col in 0..grid[0].len() {
-            if visited.contains(&Position {
-                x: col as i32,
-                y: row as i32,
-            }) {
-                line.insert(col, '#');
-            }
-        }
-        println!("Row: {:02} {}", row, line);
+    let mut frame = grid.clone();
+    frame[path[0]] = 'S';
+    frame[destination] = 'E';
+    record(recorder, || (frame.clone(), path[0]));
+    for &pos in &path[1..path.len() - 1] {
+        frame[pos] = '#';
+        record(recorder, || (frame.clone(), pos));
     }
-    println!("Elevation: {}", elevation);
+    Ok(())
 }
 
-fn find_start(grid: &Grid) -> Position {
-    for col in 0..grid[0].len() {
-        for row in 0..grid.len() {
-            if grid[row][col] == 'S' {
-                return Position {
-                    x: col as i32,
-                    y: row as i32,
-                };
-            }
-        }
-    }
-    return Position { x: 0, y: 0 };
+#[test]
+pub fn test_day12_pt_1() {
+    let input = Day12::parse(&read_string(12, false).unwrap()).unwrap();
+    assert_eq!(Day12::part1(&input).unwrap(), Answer::from(528));
 }
 
-fn find_elves(grid: &Grid) -> Position {
-    for col in 0..grid[0].len() {
-        for row in 0..grid.len() {
-            if grid[row][col] == 'E' {
-                return Position {
-                    x: col as i32,
-                    y: row as i32,
-                };
-            }
-        }
-    }
-    return Position { x: 0, y: 0 };
+/// Steps from the nearest of `starts`, climbing at most one elevation at a
+/// time, or `None` when no start can reach `destination`.
+pub fn shortest_path(
+    grid: &Grid<char>,
+    starts: impl IntoIterator<Item = Pos>,
+    destination: Pos,
+) -> Option<Found<Pos, usize>> {
+    let found = bfs(
+        starts,
+        |&pos| {
+            grid.neighbours4(pos)
+                .filter(move |&next| grid[pos] as u32 + 1 >= grid[next] as u32)
+        },
+        |&pos| pos == destination,
+    );
+    trace::event!(
+        Debug,
+        "shortest path",
+        elves = destination,
+        steps = found.as_ref().map(|found| found.cost)
+    );
+    found
+}
+
+#[test]
+pub fn test_unreachable() {
+    let err = Day12::parse("SbE").unwrap_err();
+    assert_eq!(err.to_string(), "day 12: expected E to be reachable from S");
+    assert!(Day12::parse("SbcdefghijklmnopqrstuvwxyE").is_ok());
+
+    // Maps built without parse are not checked up front.
+    let grid = Grid::parse("abz", "", Some).unwrap();
+    assert!(Day12::part1(&(grid.clone(), (0, 0), (2, 0))).is_err());
+    assert!(Day12::part2(&(grid, (0, 0), (2, 0))).is_err());
 }
 
 #[test]
 pub fn test_day12_pt_2() {
-    let lines: Vec<String> = read_lines(12, false);
-    let (grid, start, destination) = extract_grid(lines);
-
-    let mut shortest = 10000;
-    for start_y in 0..grid.len() {
-        let steps = shortest_path(
-            &grid,
-            Position {
-                x: 0,
-                y: start_y as i32,
-            },
-            &destination,
-        )
-        .unwrap();
-        if steps < shortest {
-            shortest = steps;
+    let input = Day12::parse(&read_string(12, false).unwrap()).unwrap();
+    assert_eq!(Day12::part2(&input).unwrap(), Answer::from(522));
+}
+
+/// A separate breadth-first search from every start, for differential testing
+/// against the single multi-source search above.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub(crate) fn brute_force(input: &str, params: &Params, part: Part) -> Result<Answer> {
+    let (grid, start, destination) = Day12::parse_with(input, params)?;
+    let starts: Vec<Pos> = match part {
+        Part::One => vec![start],
+        Part::Two => lowest(&grid).collect(),
+    };
+    let elevation = |pos: Pos| grid[pos] as u8;
+
+    let mut best: Option<usize> = None;
+    for start in starts {
+        let mut steps = vec![vec![None; grid.width()]; grid.height()];
+        steps[start.1][start.0] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some((x, y)) = queue.pop_front() {
+            let here = steps[y][x].unwrap();
+            let next = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in next {
+                if nx < grid.width()
+                    && ny < grid.height()
+                    && steps[ny][nx].is_none()
+                    && elevation((nx, ny)) <= elevation((x, y)) + 1
+                {
+                    steps[ny][nx] = Some(here + 1);
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+        if let Some(found) = steps[destination.1][destination.0] {
+            best = Some(best.map_or(found, |best: usize| best.min(found)));
         }
     }
-
-    println!("Part2 score is {:?}", shortest);
-    assert!(false);
+    best.map(Answer::from)
+        .ok_or_else(|| anyhow!("The destination cannot be reached"))
 }
@@ -1,31 +1,78 @@
+use anyhow::Result;
 use packet::PacketData::*;
 
 use packet::{Packet, PacketData};
 
+#[cfg(test)]
 use crate::helper::read_string;
+use crate::{
+    solution::{solution_api, Answer, Solution},
+    trace,
+};
 
-mod packet;
+pub mod packet;
 
-#[test]
-pub fn test_day13_pt_1() {
-    let input: String = read_string(13, false);
-    let pairs = packet::parse_packets(&input);
-
-    let mut sum = 0;
-    for (i, pair) in pairs.iter().enumerate() {
-        let is_right_order = compare(&pair.left, &pair.right);
-        if let Some(true) = is_right_order {
-            // print_pair(pair);
-            sum = sum + i + 1;
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<Packet>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(packet::parse_packets(input).map_err(|err| err.for_day(13))?)
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<Answer> {
+        let mut sum = 0;
+        for (i, pair) in pairs.iter().enumerate() {
+            let is_right_order = compare(&pair.left, &pair.right);
+            trace::event!(
+                Debug,
+                "pair",
+                index = i + 1,
+                right_order = is_right_order,
+                packets = format_pair(pair)
+            );
+            if let Some(true) = is_right_order {
+                sum += i + 1;
+            }
         }
-        // println!("Pair {}  - {:?}", i + 1, is_right_order)
+        Ok(sum.into())
     }
-    println!("{}", pairs.len());
-    println!("Part1 score is {:?}", sum);
-    assert_eq!(sum, 5682);
+
+    fn part2(pairs: &Self::Input) -> Result<Answer> {
+        let mut pairs: Vec<PacketData> = flatten_packets(pairs);
+        let decoder_packets = [
+            List {
+                values: vec![List {
+                    values: vec![Integer { value: 2 }],
+                }],
+            },
+            List {
+                values: vec![List {
+                    values: vec![Integer { value: 6 }],
+                }],
+            },
+        ];
+
+        pairs.push(decoder_packets[0].clone());
+        pairs.push(decoder_packets[1].clone());
+
+        pairs.sort();
+
+        let decoders: Vec<usize> = find_decoder_packets(&pairs);
+        Ok(((decoders[0] + 1) * (decoders[1] + 1)).into())
+    }
+}
+
+solution_api!(Day13);
+
+#[test]
+pub fn test_day13_pt_1() {
+    let pairs = Day13::parse(&read_string(13, false).unwrap()).unwrap();
+    assert_eq!(Day13::part1(&pairs).unwrap(), Answer::from(5682));
 }
 
-fn print_pair(pair: &Packet) {
+fn format_pair(pair: &Packet) -> String {
     let mut line = "".to_owned();
     line.push('[');
     for item in &pair.left {
@@ -42,8 +89,7 @@ fn print_pair(pair: &Packet) {
         line.push(',')
     }
     line.push(']');
-
-    println!("{}", line);
+    line
 }
 
 fn print_data(data: &PacketData) -> String {
@@ -63,41 +109,47 @@ fn print_data(data: &PacketData) -> String {
     data_str
 }
 
-pub fn compare(left: &Vec<PacketData>, right: &Vec<PacketData>) -> Option<bool> {
-    // println!("Compare {:?} vs {:?}", left, right);
+/// Whether `left` comes before `right`, `None` when neither decides.
+pub fn compare(left: &[PacketData], right: &[PacketData]) -> Option<bool> {
+    trace::event!(Trace, "compare", left = left, right = right);
     for i in 0..left.len().max(right.len()) {
         match (left.get(i), right.get(i)) {
             (None, Some(_)) => return Some(true),
             (Some(_), None) => return Some(false),
             (Some(Integer { value: left_value }), Some(Integer { value: right_value })) => {
                 if left_value < right_value {
-                    // println!("Left side is smaller {} vs {}", left_value, right_value);
+                    trace::event!(
+                        Trace,
+                        "left side is smaller",
+                        left = left_value,
+                        right = right_value
+                    );
                     return Some(true);
                 } else if right_value < left_value {
-                    // println!("Right side is smaller {} vs {}", left_value, right_value);
+                    trace::event!(
+                        Trace,
+                        "right side is smaller",
+                        left = left_value,
+                        right = right_value
+                    );
                     return Some(false);
                 }
-
-                ()
             }
             (
                 Some(List { values: left_value }),
                 Some(List {
                     values: right_value,
                 }),
-            ) => {
-                // println!("Comparing {:?} vs {:?}", left_value, right_value);
-                match compare(left_value, right_value) {
-                    Some(true) => return Some(true),
-                    Some(false) => return Some(false),
-                    None => (),
-                }
-            }
+            ) => match compare(left_value, right_value) {
+                Some(true) => return Some(true),
+                Some(false) => return Some(false),
+                None => (),
+            },
 
             (Some(List { values: left_value }), Some(Integer { value: right_value })) => {
                 match compare(
                     left_value,
-                    &vec![Integer {
+                    &[Integer {
                         value: *right_value,
                     }],
                 ) {
@@ -112,11 +164,13 @@ pub fn compare(left: &Vec<PacketData>, right: &Vec<PacketData>) -> Option<bool>
                     values: right_value,
                 }),
             ) => {
-                // println!(
-                //     "Lifting left value {:?} to match {:?}",
-                //     left_value, right_value
-                // );
-                match compare(&vec![Integer { value: *left_value }], right_value) {
+                trace::event!(
+                    Trace,
+                    "lifting left value",
+                    left = left_value,
+                    right = right_value
+                );
+                match compare(&[Integer { value: *left_value }], right_value) {
                     Some(true) => return Some(true),
                     Some(false) => {
                         return Some(false);
@@ -128,48 +182,22 @@ pub fn compare(left: &Vec<PacketData>, right: &Vec<PacketData>) -> Option<bool>
         };
     }
 
-    return None;
+    None
 }
 
 #[test]
 pub fn test_day13_pt_2() {
-    let input: String = read_string(13, false);
-    let mut pairs: Vec<PacketData> = flatten_packets(input);
-    let decoder_packets = [
-        List {
-            values: vec![List {
-                values: vec![Integer { value: 2 }],
-            }],
-        },
-        List {
-            values: vec![List {
-                values: vec![Integer { value: 6 }],
-            }],
-        },
-    ];
-
-    pairs.push(decoder_packets[0].clone());
-    pairs.push(decoder_packets[1].clone());
-
-    pairs.sort();
-
-    let decoders: Vec<usize> = find_decoder_packets(&pairs);
-    for pair in pairs {
-        println!("{}", &print_data(&pair));
-    }
-    println!("{:?}", &decoders);
-    let score = (decoders[0] + 1) * (decoders[1] + 1);
-    println!("Part2 score is {:?}", score);
-    assert_eq!(score, 20304);
+    let pairs = Day13::parse(&read_string(13, false).unwrap()).unwrap();
+    assert_eq!(Day13::part2(&pairs).unwrap(), Answer::from(20304));
 }
 
-fn find_decoder_packets(pairs: &Vec<PacketData>) -> Vec<usize> {
+fn find_decoder_packets(pairs: &[PacketData]) -> Vec<usize> {
     pairs
         .iter()
         .enumerate()
         .filter_map(|(i, p)| match p {
-            List { values } if values.len() == 1 => match values.get(0) {
-                Some(List { values }) if values.len() == 1 => match values.get(0) {
+            List { values } if values.len() == 1 => match values.first() {
+                Some(List { values }) if values.len() == 1 => match values.first() {
                     Some(Integer { value: 6 }) => Some(i),
                     Some(Integer { value: 2 }) => Some(i),
                     _ => None,
@@ -181,8 +209,8 @@ fn find_decoder_packets(pairs: &Vec<PacketData>) -> Vec<usize> {
         .collect()
 }
 
-fn flatten_packets(input: String) -> Vec<PacketData> {
-    packet::parse_packets(&input)
+fn flatten_packets(pairs: &[Packet]) -> Vec<PacketData> {
+    pairs
         .iter()
         .flat_map(|pair| {
             vec![
@@ -1,14 +1,11 @@
-use std::{cmp::Ordering, collections::HashSet, error::Error, hash::Hash, str::FromStr};
+use std::cmp::Ordering;
 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take_while},
-    character::complete::{self, digit1, newline, one_of},
-    combinator::map_res,
-    error::{ErrorKind, ParseError},
-    multi::{separated_list0, separated_list1},
-    sequence::{delimited, pair, preceded, terminated, tuple},
-    IResult, Parser,
+    character::complete::{char, newline},
+    multi::separated_list0,
+    sequence::{delimited, separated_pair},
+    Parser,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -18,6 +15,10 @@ pub enum PacketData {
 }
 
 use super::compare;
+use crate::{
+    error::ParseError,
+    parse::{blocks, parse_all, unsigned, IResult},
+};
 use PacketData::*;
 
 #[derive(Debug, Clone)]
@@ -28,7 +29,7 @@ pub struct Packet {
 
 impl Ord for PacketData {
     fn cmp(&self, other: &Self) -> Ordering {
-        match compare(&vec![self.clone()], &vec![other.clone()]) {
+        match compare(std::slice::from_ref(self), std::slice::from_ref(other)) {
             Some(false) => Ordering::Greater,
             Some(true) => Ordering::Less,
             None => Ordering::Equal,
@@ -42,75 +43,83 @@ impl PartialOrd for PacketData {
     }
 }
 
-pub fn parse_packets(input: &str) -> Vec<Packet> {
-    let (_, packets) = separated_list1(
-        newline,
-        tuple((new_parse, new_parse)).map(|(first, second)| Packet {
-            left: if let List { values } = first {
-                values
-            } else {
-                panic!("Should be list")
-            },
-            right: if let List { values } = second {
-                values
-            } else {
-                panic!("Should be list")
-            },
-        }),
-    )(input)
-    .unwrap();
-
-    packets
+/// Pairs of packets, one per line, with a blank line between pairs.
+pub fn parse_packets(input: &str) -> Result<Vec<Packet>, ParseError> {
+    parse_all(
+        input,
+        blocks(
+            separated_pair(parse_packet, newline, parse_packet).map(|(left, right)| Packet {
+                left: into_values(left),
+                right: into_values(right),
+            }),
+        ),
+    )
 }
 
-fn new_parse(input: &str) -> IResult<&str, PacketData> {
-    let mut current: Vec<PacketData> = Vec::new();
-    let mut rest = input.clone();
-    loop {
-        if rest.is_empty() {
-            break;
-        }
+fn into_values(data: PacketData) -> Vec<PacketData> {
+    match data {
+        List { values } => values,
+        Integer { .. } => vec![data],
+    }
+}
 
-        if let Ok((tail, result)) = alt((
-            delimited(tag("["), new_parse, tag("]")),
-            complete::u32.map(|num| Integer {
-                value: num as usize,
-            }),
-        ))(rest)
-        {
-            current.push(result);
-            rest = tail;
-        }
+/// A bracketed, comma separated list of integers and lists.
+fn parse_packet(input: &str) -> IResult<'_, PacketData> {
+    delimited(
+        char('['),
+        separated_list0(
+            char(','),
+            alt((parse_packet, unsigned.map(|value| Integer { value }))),
+        ),
+        char(']'),
+    )
+    .map(|values| List { values })
+    .parse(input)
+}
 
-        if let Ok((new_tail, _)) = tag::<_, _, (_, ErrorKind)>(",")(rest) {
-            rest = new_tail;
-        } else if let Ok((new_tail, _)) = newline::<_, (_, ErrorKind)>(rest) {
-            rest = new_tail;
-            break;
-        } else {
-            break;
+#[test]
+pub fn test_parse_packet() {
+    let (_, data) = parse_packet("[1,[[2]],3]").unwrap();
+    assert_eq!(
+        data,
+        List {
+            values: vec![
+                Integer { value: 1 },
+                List {
+                    values: vec![List {
+                        values: vec![Integer { value: 2 }]
+                    }]
+                },
+                Integer { value: 3 },
+            ]
         }
-    }
-
-    Ok((rest, List { values: current }))
+    );
 }
 
 #[test]
-pub fn test_new_parse() {
-    let (input, data) = new_parse("[1,2,3,4,5]").unwrap();
-    let (input, data) = new_parse("[1,[[2]],3,4,5]").unwrap();
-    println!("{:?}", data);
-    assert!(false)
+pub fn test_parse_packets_junk() {
+    let err = parse_packets("[1]\n[2]\n\n[3]\n[4,x]\n").unwrap_err();
+    assert_eq!((err.line, err.column), (Some(5), Some(3)));
+    assert!(parse_packets("[1]\n[2]\n\n[3]\n[4]\njunk\n").is_err());
 }
 
 #[test]
 pub fn test_list() {
+    let list = |values: &[usize]| List {
+        values: values.iter().map(|&value| Integer { value }).collect(),
+    };
+
     let line = "[9,3,3,10,7]";
-    let (_, data) = new_parse(line).unwrap();
-    println!("Data: {:?}", data);
+    let (_, data) = parse_packet(line).unwrap();
+    assert_eq!(data, list(&[9, 3, 3, 10, 7]));
 
     let line = "[[1],[2,3,4]]";
 
-    let (_, data) = new_parse(line).unwrap();
-    println!("Data: {:?}", data);
+    let (_, data) = parse_packet(line).unwrap();
+    assert_eq!(
+        data,
+        List {
+            values: vec![list(&[1]), list(&[2, 3, 4])]
+        }
+    );
 }
@@ -1,46 +1,93 @@
-mod structure;
+pub mod structure;
 
-use std::{collections::HashSet, hash::Hash, str::FromStr};
+use anyhow::Result;
 
+#[cfg(test)]
+use crate::helper::read_string;
 use crate::{
-    day14::structure::{find_max_y, parse_paths, Cave, Line, Path},
-    helper::{read_lines, read_string, ParseError},
+    day14::structure::{find_max_y, parse_paths, Cave, Line, Path, SAND_ENTRY},
+    solution::{solution_api, Answer, Part, Solution},
+    visual::{record, Recorder},
 };
 
-#[test]
-pub fn test_day14_pt_1() {
-    let input: String = read_string(14, false);
-    let paths = parse_paths(&input);
-    let mut cave = Cave::new(paths);
+pub struct Day14;
 
-    let mut units = 0;
-    while cave.next() {
-        units = units + 1;
-        // println!("{}", cave.to_string());
+impl Solution for Day14 {
+    type Input = Vec<Path>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_paths(input).map_err(|err| err.for_day(14))?)
+    }
+
+    fn part1(paths: &Self::Input) -> Result<Answer> {
+        Ok(pour_sand(Cave::new(paths.clone()), &mut ()).into())
+    }
+
+    fn part2(paths: &Self::Input) -> Result<Answer> {
+        Ok(
+            // The unit that plugs the source comes to rest as well.
+            (pour_sand(Cave::new(with_floor(paths)), &mut ()) + 1).into(),
+        )
     }
-    println!("Part1 score is {:?}", units);
-    assert!(false);
 }
 
-#[test]
-pub fn test_day14_pt_2() {
-    let input: String = read_string(14, false);
-    let mut paths = parse_paths(&input);
-    let max_y = find_max_y(&paths);
+solution_api!(Day14);
+
+/// Frames of the cave after every unit of sand, for `aoc record`.
+pub fn simulate(input: &str, part: Part, recorder: &mut dyn Recorder) -> Result<()> {
+    let paths = Day14::parse(input)?;
+    let cave = match part {
+        Part::One => Cave::new(paths),
+        Part::Two => Cave::new(with_floor(&paths)),
+    };
+    pour_sand(cave, recorder);
+    Ok(())
+}
+
+/// The paths plus the floor two below the lowest rock, as wide as the sand
+/// can spread on the way down to it.
+fn with_floor(paths: &[Path]) -> Vec<Path> {
+    let mut paths = paths.to_vec();
+    let floor = find_max_y(&paths) + 2;
     paths.push(Path {
         lines: vec![Line {
-            start: (0, max_y + 2),
-            end: (2000, max_y + 2),
+            start: (SAND_ENTRY.0 - floor, floor),
+            end: (SAND_ENTRY.0 + floor, floor),
         }],
     });
-    let mut cave = Cave::new(paths);
-    println!("{}", cave.to_string());
+    paths
+}
 
+/// Drops sand until a unit falls out or the source is blocked, and counts the
+/// units that came to rest.
+fn pour_sand(mut cave: Cave, recorder: &mut dyn Recorder) -> usize {
     let mut units = 0;
-    while cave.next() {
-        units = units + 1;
-        // println!("{}", cave.to_string());
+    record(recorder, || (cave.frame(), cave.focus()));
+    while cave.drop_sand() {
+        units += 1;
+        record(recorder, || (cave.frame(), cave.focus()));
     }
-    println!("Part2 score is {:?}", units);
-    assert!(false);
+    units
+}
+
+#[test]
+pub fn test_day14_pt_1() {
+    let paths = Day14::parse(&read_string(14, false).unwrap()).unwrap();
+    assert_eq!(Day14::part1(&paths).unwrap(), Answer::from(1003));
+}
+
+#[test]
+pub fn test_day14_pt_2() {
+    let paths = Day14::parse(&read_string(14, false).unwrap()).unwrap();
+    assert_eq!(Day14::part2(&paths).unwrap(), Answer::from(25771));
+}
+
+/// Vertical rock lines end on their last rock, and the unit that plugs the
+/// source in part 2 comes to rest too.
+#[test]
+pub fn test_rock_lines_and_plug() {
+    let paths = Day14::parse("499,2 -> 499,5\n495,6 -> 505,6").unwrap();
+    assert_eq!(Day14::part1(&paths).unwrap(), Answer::from(21));
+    let paths = Day14::parse("500,3 -> 500,5 -> 497,5").unwrap();
+    assert_eq!(Day14::part2(&paths).unwrap(), Answer::from(41));
 }
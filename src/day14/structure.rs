@@ -1,21 +1,20 @@
-use nom::{
-    branch::alt,
-    bytes::complete::{tag, take_while},
-    character::complete::{self, digit1, newline, one_of},
-    combinator::map_res,
-    error::{ErrorKind, ParseError},
-    multi::{separated_list0, separated_list1},
-    sequence::{delimited, pair, preceded, terminated, tuple},
-    IResult, Parser,
-};
+use std::fmt;
+
+use nom::{bytes::complete::tag, multi::separated_list1, sequence::separated_pair};
 
-type Grid = Vec<Vec<Cell>>;
+use crate::{
+    error::ParseError,
+    grid::{Grid, Pos},
+    parse::{lines, parse_all, unsigned},
+    point::Point,
+    trace,
+    visual::Frame,
+};
 
 #[derive(Debug, Clone)]
 pub enum Cell {
     Empty,
     Rock,
-    Sand,
     Rest,
 }
 
@@ -30,20 +29,44 @@ pub struct Path {
     pub lines: Vec<Line>,
 }
 
-fn parse_path(input: &str) -> Vec<Vec<(u32, u32)>> {
-    let (input, matches) = separated_list1::<_, _, _, (_, ErrorKind), _, _>(
-        newline,
-        separated_list1(
+fn parse_path(input: &str) -> Result<Vec<Vec<(u32, u32)>>, ParseError> {
+    parse_all(
+        input,
+        lines(separated_list1(
             tag(" -> "),
-            tuple((complete::u32, preceded(tag(","), complete::u32))),
-        ),
-    )(input)
-    .unwrap();
-    matches
+            separated_pair(unsigned, tag(","), unsigned),
+        )),
+    )
 }
 
-pub fn parse_paths(input: &str) -> Vec<Path> {
-    parse_path(input)
+pub const SAND_ENTRY: Pos = (500, 0);
+
+/// Sand spreads at most one column per row, so with the floor two below the
+/// lowest rock, at most `MAX_Y + 2` rows down, it stays within `0..=MAX_X`.
+/// Rocks past these are rejected rather than sizing the cave after them.
+pub const MAX_X: u32 = 2 * SAND_ENTRY.0 as u32;
+pub const MAX_Y: u32 = SAND_ENTRY.0 as u32 - 2;
+
+pub fn parse_paths(input: &str) -> Result<Vec<Path>, ParseError> {
+    let paths = parse_path(input)?;
+    for (i, path) in paths.iter().enumerate() {
+        let text = input.lines().nth(i).unwrap_or("");
+        if path.iter().any(|&(x, y)| x > MAX_X || y > MAX_Y) {
+            let msg = format!("expected points within {},{}", MAX_X, MAX_Y);
+            return Err(ParseError::new(&msg, text).at_line(i + 1));
+        }
+        if path.len() < 2 {
+            return Err(ParseError::new("expected at least two points", text).at_line(i + 1));
+        }
+        if path
+            .windows(2)
+            .any(|window| window[0].0 != window[1].0 && window[0].1 != window[1].1)
+        {
+            return Err(ParseError::new("expected straight lines", text).at_line(i + 1));
+        }
+    }
+
+    Ok(paths
         .iter()
         .map(|path| {
             let lines = path
@@ -53,178 +76,177 @@ pub fn parse_paths(input: &str) -> Vec<Path> {
                     end: (window[1].0 as usize, window[1].1 as usize),
                 })
                 .collect();
-            // println!("{:?}", lines);
+            trace::event!(Trace, "path", lines = lines);
             Path { lines }
         })
-        .collect()
-}
-
-#[derive(Debug, Clone)]
-pub struct Position {
-    x: usize,
-    y: usize,
+        .collect())
 }
 
 #[derive(Debug, Clone)]
 pub struct Cave {
-    sand_entry: Position,
-    grid: Grid,
+    sand_entry: Pos,
+    last_sand: Pos,
+    grid: Grid<Cell>,
     x_min: usize,
     x_max: usize,
 }
 
-impl ToString for Cave {
-    fn to_string(&self) -> String {
-        let mut lines = Vec::new();
-        for (row_index, row) in self.grid.iter().enumerate() {
-            let mut line = format!("Row: {}", row_index).to_owned();
-            for col in self.x_min..self.x_max + 1 {
-                if row_index == 0 && col == self.sand_entry.x {
-                    line.push('+');
-                } else {
-                    let c = match row[col] {
-                        Cell::Empty => '.',
-                        Cell::Rest => 'o',
-                        Cell::Sand => 'o',
-                        Cell::Rock => '#',
-                    };
-
-                    line.push(c);
-                }
-            }
-            lines.push(line);
+impl From<&Cell> for char {
+    fn from(cell: &Cell) -> char {
+        match cell {
+            Cell::Empty => '.',
+            Cell::Rest => 'o',
+            Cell::Rock => '#',
         }
+    }
+}
 
-        lines.join("\n")
+/// The part of the cave between the leftmost and rightmost rock, with `+`
+/// for the sand source.
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frame = self.frame();
+        for y in 0..frame.height() {
+            let row: String = frame.row(y).iter().collect();
+            writeln!(f, "Row: {} {}", y, row)?;
+        }
+        Ok(())
     }
 }
 
 impl Cave {
+    pub fn frame(&self) -> Frame {
+        let width = self.x_max - self.x_min + 1;
+        let mut frame = Grid::new(width, self.grid.height(), '.');
+        for pos in frame.positions() {
+            let cave_pos = (pos.0 + self.x_min, pos.1);
+            frame[pos] = if cave_pos == self.sand_entry {
+                '+'
+            } else {
+                char::from(&self.grid[cave_pos])
+            };
+        }
+        frame
+    }
+
+    /// Where the last unit of sand came to rest, in [`Cave::frame`]
+    /// coordinates.
+    pub fn focus(&self) -> Pos {
+        (self.last_sand.0 - self.x_min, self.last_sand.1)
+    }
+
     pub fn new(paths: Vec<Path>) -> Cave {
         let min_x = find_min_x(&paths);
         let max_x = find_max_x(&paths);
         let max_y = find_max_y(&paths);
 
-        let mut grid = vec![vec![Cell::Empty; max_x + 1]; max_y + 1];
+        let mut grid = Grid::new(max_x + 1, max_y + 1, Cell::Empty);
 
         add_rocks(paths, &mut grid);
 
         Cave {
-            sand_entry: Position { x: 500, y: 0 },
+            sand_entry: SAND_ENTRY,
+            last_sand: SAND_ENTRY,
             grid,
             x_min: min_x,
             x_max: max_x,
         }
     }
 
-    pub fn next(&mut self) -> bool {
-        let mut sand = self.sand_entry.clone();
+    /// Drops one unit of sand, false once it falls out of the cave or the
+    /// entry is blocked.
+    pub fn drop_sand(&mut self) -> bool {
+        let mut sand = self.sand_entry;
         let mut found = false;
 
-        while let Some(new_position) = find_next_sand_position(&sand, &self.grid) {
+        while let Some(new_position) = find_next_sand_position(sand, &self.grid) {
             sand = new_position;
             found = true;
 
-            if sand.x < self.x_min || self.x_max < sand.x || sand.y == self.grid.len() - 1 {
+            if sand.0 < self.x_min || self.x_max < sand.0 || sand.1 == self.grid.height() - 1 {
                 return false;
             }
         }
 
-        self.grid[sand.y][sand.x] = Cell::Rest;
+        self.grid[sand] = Cell::Rest;
+        self.last_sand = sand;
         found
     }
 }
 
-fn find_next_sand_position(sand: &Position, grid: &Grid) -> Option<Position> {
-    if let Cell::Empty = grid[sand.y + 1][sand.x] {
-        Some(Position {
-            x: sand.x,
-            y: sand.y + 1,
-        })
-    } else if let Some(&Cell::Empty) = grid.get(sand.y + 1).and_then(|row| row.get(sand.x - 1)) {
-        Some(Position {
-            x: sand.x - 1,
-            y: sand.y + 1,
-        })
-    } else if let Some(&Cell::Empty) = grid.get(sand.y + 1).and_then(|row| row.get(sand.x + 1)) {
-        Some(Position {
-            x: sand.x + 1,
-            y: sand.y + 1,
-        })
-    } else {
-        None
-    }
+/// Straight down, then down to the left, then down to the right.
+fn find_next_sand_position(sand: Pos, grid: &Grid<Cell>) -> Option<Pos> {
+    [Point::new(0, 1), Point::new(-1, 1), Point::new(1, 1)]
+        .into_iter()
+        .filter_map(|step| grid.step(sand, step))
+        .find(|&pos| matches!(grid[pos], Cell::Empty))
 }
 
-fn add_rocks(paths: Vec<Path>, grid: &mut Vec<Vec<Cell>>) {
+fn add_rocks(paths: Vec<Path>, grid: &mut Grid<Cell>) {
     for path in paths {
         for line in path.lines {
-            // println!("{:?}", line);
-            if (line.start.0 == line.end.0) {
-                let mut rows = [line.start.1, line.end.1];
-                rows.sort();
-                for row in rows[0]..rows[1] {
-                    grid[row][line.start.0] = Cell::Rock;
-                }
-            } else {
-                let mut cols = [line.start.0, line.end.0];
-                cols.sort();
-                for col in cols[0]..cols[1] + 1 {
-                    grid[line.start.1][col] = Cell::Rock;
+            trace::event!(Trace, "rock line", line = line);
+            let (x_start, x_end) = (line.start.0.min(line.end.0), line.start.0.max(line.end.0));
+            let (y_start, y_end) = (line.start.1.min(line.end.1), line.start.1.max(line.end.1));
+            for x in x_start..=x_end {
+                for y in y_start..=y_end {
+                    grid[(x, y)] = Cell::Rock;
                 }
             }
         }
     }
 }
 
-pub fn find_max_y(paths: &Vec<Path>) -> usize {
+fn points(paths: &[Path]) -> impl Iterator<Item = (usize, usize)> + '_ {
     paths
         .iter()
-        .map(|p| {
-            p.lines
-                .iter()
-                .map(|l| l.end.1.max(l.start.1))
-                .max()
-                .unwrap()
-        })
-        .max()
-        .unwrap()
+        .flat_map(|path| &path.lines)
+        .flat_map(|line| [line.start, line.end])
 }
 
-fn find_max_x(paths: &Vec<Path>) -> usize {
-    paths
-        .iter()
-        .map(|p| {
-            p.lines
-                .iter()
-                .map(|l| l.end.0.max(l.start.0))
-                .max()
-                .unwrap()
-        })
-        .max()
-        .unwrap()
+pub fn find_max_y(paths: &[Path]) -> usize {
+    points(paths).map(|(_, y)| y).max().unwrap_or(0)
 }
 
-fn find_min_x(paths: &Vec<Path>) -> usize {
-    paths
-        .iter()
-        .map(|p| {
-            p.lines
-                .iter()
-                .map(|l| l.end.0.min(l.start.0))
-                .min()
-                .unwrap()
-        })
-        .min()
-        .unwrap()
+/// Never left of the sand entry, so the grid always holds it.
+fn find_max_x(paths: &[Path]) -> usize {
+    points(paths).map(|(x, _)| x).fold(SAND_ENTRY.0, usize::max)
+}
+
+/// Never right of the sand entry.
+fn find_min_x(paths: &[Path]) -> usize {
+    points(paths).map(|(x, _)| x).fold(SAND_ENTRY.0, usize::min)
 }
 
 #[test]
 pub fn test_paths() {
     let input = "498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9";
 
-    let paths = parse_paths(input);
-    println!("{:?}", paths);
-
+    let paths = parse_paths(input).unwrap();
     assert!(paths.len() == 2);
 }
+
+#[test]
+pub fn test_add_rocks() {
+    let cave = Cave::new(parse_paths("500,2 -> 500,4 -> 498,4").unwrap());
+    for pos in [(500, 2), (500, 3), (500, 4), (499, 4), (498, 4)] {
+        assert!(matches!(cave.grid[pos], Cell::Rock), "{:?}", pos);
+    }
+    assert!(matches!(cave.grid[(500, 1)], Cell::Empty));
+}
+
+#[test]
+pub fn test_cave_bounds() {
+    assert!(parse_paths("").is_err());
+    assert!(parse_paths("2147483647,0 -> 2147483647,1").is_err());
+    assert!(parse_paths("1000,497 -> 1000,498").is_ok());
+    assert!(parse_paths("400,499 -> 401,499").is_err());
+
+    for input in ["1,1 -> 1,2", "600,3 -> 601,3"] {
+        let mut cave = Cave::new(parse_paths(input).unwrap());
+        assert!(cave.frame().row(0).contains(&'+'), "{}", input);
+        assert!(!cave.drop_sand(), "{}", input);
+    }
+    let mut cave = Cave::new(Vec::new());
+    assert!(!cave.drop_sand());
+}
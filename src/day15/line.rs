@@ -1,19 +1,21 @@
-use super::sensor::Position;
+use crate::point::Point;
 
+/// `y = k * x + m`, worked out in `i64` so sensors near the edges of `i32`
+/// cannot overflow.
 pub struct Line {
-    k: i32,
-    m: i32,
-    x_min: i32,
-    x_max: i32,
+    k: i64,
+    m: i64,
+    x_min: i64,
+    x_max: i64,
 }
 
 impl Line {
     pub fn new(km: (i32, i32), x_range: (i32, i32)) -> Line {
         Line {
-            k: km.0,
-            m: km.1,
-            x_min: x_range.0,
-            x_max: x_range.1,
+            k: km.0.into(),
+            m: km.1.into(),
+            x_min: x_range.0.into(),
+            x_max: x_range.1.into(),
         }
     }
 
@@ -28,13 +30,13 @@ impl Line {
         false
     }
 
-    pub fn intersection(&self, other: &Line) -> Option<Position> {
-        if (self.k == other.k && self.m != other.m) {
+    pub fn intersection(&self, other: &Line) -> Option<Point> {
+        if self.k == other.k && self.m != other.m {
             None
         } else {
             let x = (other.m - self.m) / (self.k - other.k);
             let y = self.k * x + self.m;
-            Some(Position { x, y })
+            Some(Point::new(x.try_into().ok()?, y.try_into().ok()?))
         }
     }
 }
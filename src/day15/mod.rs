@@ -1,126 +1,192 @@
 mod line;
 mod part1;
 mod part2;
-mod sensor;
+pub mod sensor;
 
-use std::{
-    collections::{HashSet, VecDeque},
-    hash::Hash,
-    str::FromStr,
-};
+use anyhow::{anyhow, Result};
 
+#[cfg(test)]
+use crate::helper::read_string;
 use crate::{
-    day15::{line::Line, sensor::parse_sensor},
-    helper::{read_lines, ParseError},
+    day15::{line::Line, part1::setup_grid, part2::tuning_frequency, sensor::parse_sensor},
+    error::{parse_lines, ParseError},
+    parse::parse_all,
+    point::Point,
+    solution::{solution_api, Answer, Params, Part, Solution},
+    trace,
 };
 
-use self::sensor::{print_grid, Position, Sensor};
+use self::sensor::Sensor;
+
+pub struct Day15;
+
+/// Part 1 asks about row 2000000 and part 2 searches 0..=4000000 in both
+/// directions. The example asks about smaller ones, given as the `row` and
+/// `limit` params.
+pub const ROW: i32 = 2_000_000;
+pub const LIMIT: i32 = 4_000_000;
+
+/// The sensors and the area the puzzle asks about.
+#[derive(Debug, Clone)]
+pub struct Scan {
+    pub sensors: Vec<Sensor>,
+    pub row: i32,
+    pub limit: i32,
+}
+
+impl Solution for Day15 {
+    type Input = Scan;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Self::parse_with(input, &Params::new())
+    }
+
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        params.check(&["row", "limit"])?;
+        let limit = params.get_or("limit", LIMIT)?;
+        if limit < 0 {
+            return Err(anyhow!("day 15: limit must not be negative, got {}", limit));
+        }
+        Ok(Scan {
+            sensors: parse_lines(15, input, sensor_line)?,
+            row: params.get_or("row", ROW)?,
+            limit,
+        })
+    }
+
+    fn part1(scan: &Self::Input) -> Result<Answer> {
+        Ok(setup_grid(&scan.sensors, scan.row).into())
+    }
+
+    fn part2(scan: &Self::Input) -> Result<Answer> {
+        Ok(tuning_frequency(&scan.sensors, scan.limit)?.into())
+    }
+}
 
-fn find_adjacent_sensors(sensors: &Vec<Sensor>, limit: i32) -> Position {
-    let lower = &Position { x: 0, y: 0 };
-    let upper = &Position { x: limit, y: limit };
+solution_api!(Day15);
+
+fn sensor_line(line: &str) -> Result<Sensor, ParseError> {
+    let (position, beacon) = parse_all(line, parse_sensor)?;
+    Sensor::new(position, beacon)
+        .ok_or_else(|| ParseError::new("expected the sensor's reach to fit in 32 bits", line))
+}
+
+/// The position in 0..=`limit` no sensor covers, where two pairs of sensors
+/// leave a one wide gap between them.
+fn find_adjacent_sensors(sensors: &[Sensor], limit: i32) -> Option<Point> {
+    let lower = &Point::ORIGIN;
+    let upper = &Point::new(limit, limit);
     for sensor_a in sensors {
-        println!("Outer Sensor A {:?}", sensor_a.position);
-        if sensor_a.within_bounds(&lower, &upper) {
+        trace::event!(Debug, "outer sensor", position = sensor_a.position);
+        if sensor_a.within_bounds(lower, upper) {
             for sensor_b in sensors {
-                if sensor_b.within_bounds(&lower, &upper) {
-                    if sensor_a.position != sensor_b.position
-                        && Line::new(sensor_a.lines[0], sensor_a.x_range)
-                            .perpendicular(&Line::new(sensor_b.lines[1], sensor_b.x_range))
-                    {
-                        println!("Lines match {:?} - {:?}", sensor_a, sensor_b);
-
-                        for sensor_c in sensors {
-                            for sensor_d in sensors {
-                                if sensor_c.position != sensor_d.position
-                                    && Line::new(sensor_c.lines[2], sensor_c.x_range).perpendicular(
+                if sensor_b.within_bounds(lower, upper)
+                    && sensor_a.position != sensor_b.position
+                    && Line::new(sensor_a.lines[0], sensor_a.x_range)
+                        .perpendicular(&Line::new(sensor_b.lines[1], sensor_b.x_range))
+                {
+                    trace::event!(
+                        Debug,
+                        "lines match",
+                        a = sensor_a.position,
+                        b = sensor_b.position
+                    );
+
+                    for sensor_c in sensors {
+                        for sensor_d in sensors {
+                            if sensor_c.position != sensor_d.position
+                                && Line::new(sensor_c.lines[2], sensor_c.x_range)
+                                    .perpendicular(&Line::new(sensor_d.lines[3], sensor_d.x_range))
+                            {
+                                if let Some(intersection) =
+                                    Line::new(sensor_a.lines[0], sensor_a.x_range).intersection(
                                         &Line::new(sensor_d.lines[3], sensor_d.x_range),
                                     )
                                 {
-                                    if let Some(intersection) =
-                                        Line::new(sensor_a.lines[0], sensor_a.x_range).intersection(
-                                            &Line::new(sensor_d.lines[3], sensor_d.x_range),
-                                        )
-                                    {
-                                        if intersection.within(&lower, &upper) {
-                                            return intersection;
-                                        }
+                                    if intersection.within(*lower, *upper) {
+                                        return Some(intersection);
                                     }
                                 }
                             }
                         }
                     }
-
-                    // for sensor_c in sensors {
-                    //     if sensor_c.within_bounds(&lower, &upper)
-                    //         && sensor_b.position != sensor_c.position
-                    //         && sensor_a.position != sensor_c.position
-                    //     {
-                    //         for sensor_d in sensors {
-                    //             println!("Inner Sensor D {:?}", sensor_d.position);
-                    //             if sensor_d.within_bounds(&lower, &upper)
-                    //                 && sensor_c.position != sensor_d.position
-                    //                 && sensor_b.position != sensor_d.position
-                    //                 && sensor_a.position != sensor_d.position
-                    //             {
-                    //                 let a: HashSet<Position> =
-                    //                     HashSet::from_iter(sensor_a.line_points());
-                    //                 let b: HashSet<Position> =
-                    //                     HashSet::from_iter(sensor_b.line_points());
-                    //                 let c: HashSet<Position> =
-                    //                     HashSet::from_iter(sensor_c.line_points());
-                    //                 let d: HashSet<Position> =
-                    //                     HashSet::from_iter(sensor_d.line_points());
-
-                    //                 let ab: HashSet<Position> =
-                    //                     HashSet::from_iter(a.intersection(&b).copied());
-                    //                 let abc = HashSet::from_iter(ab.intersection(&c).copied());
-                    //                 let unique: Vec<Position> =
-                    //                     abc.intersection(&d).copied().collect();
-
-                    //                 if unique.len() == 1
-                    //                     && !sensors.iter().any(|s| s.contains(&unique[0]))
-                    //                 {
-                    //                     // print_sensor_outline(
-                    //                     //     &lower,
-                    //                     //     &upper,
-                    //                     //     &vec![&sensor_a, &sensor_b, &sensor_c, &sensor_d],
-                    //                     // );
-                    //                     println!("{:?}", unique);
-                    //                     println!(
-                    //                         "{:?}",
-                    //                         vec![sensor_a, sensor_b, sensor_c, sensor_d]
-                    //                     );
-
-                    //                     return unique[0];
-                    //                 }
-                    //             }
-                    //         }
-                    //     }
-                    // }
                 }
             }
         }
     }
 
-    Position { x: 0, y: 0 }
+    None
 }
 
-fn print_sensor_outline(lower: &Position, upper: &Position, sensors: &Vec<&Sensor>) {
-    let mut grid = vec![vec![' '; 21]; 21];
+/// Checks every position one at a time, for differential testing against the
+/// interval and sensor line searches. Only small areas like the example's are
+/// small enough for that.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub(crate) fn brute_force(input: &str, params: &Params, part: Part) -> Result<Answer> {
+    let Scan {
+        sensors,
+        row,
+        limit,
+    } = Day15::parse_with(input, params)?;
+    if limit > 100 {
+        return Err(anyhow!(
+            "Only search areas up to 0..=100 can be brute forced"
+        ));
+    }
+    let covered = |point: Point| {
+        sensors
+            .iter()
+            .any(|sensor| match sensor.position.checked_manhattan(point) {
+                Some(distance) => distance <= sensor.manhattan_distance,
+                None => false,
+            })
+    };
 
-    for sensor in sensors {
-        for pos in sensor.line_points() {
-            if pos.within(lower, upper) {
-                grid[pos.y as usize][pos.x as usize] = '#';
-            }
+    match part {
+        Part::One => {
+            let reach = sensors
+                .iter()
+                .map(|sensor| sensor.manhattan_distance)
+                .max()
+                .unwrap_or(0);
+            let xs = sensors.iter().map(|sensor| sensor.position.x);
+            let (min_x, max_x) = (xs.clone().min().unwrap_or(0), xs.max().unwrap_or(0));
+            let count = (min_x - reach..=max_x + reach)
+                .map(|x| Point::new(x, row))
+                .filter(|&point| {
+                    covered(point) && sensors.iter().all(|sensor| sensor.closest_beacon != point)
+                })
+                .count();
+            Ok(count.into())
         }
+        Part::Two => (0..=limit)
+            .flat_map(|y| (0..=limit).map(move |x| Point::new(x, y)))
+            .find(|&point| !covered(point))
+            .map(|point| Answer::from(i64::from(point.x) * 4_000_000 + i64::from(point.y)))
+            .ok_or_else(|| anyhow!("Every position is covered")),
     }
+}
 
-    for sensor in sensors {
-        grid[sensor.position.y as usize][sensor.position.x as usize] = 'S';
-    }
-    for (i, line) in grid.iter().enumerate() {
-        println!("Row: {: ^2} {}", i, line.iter().collect::<String>());
-    }
+#[test]
+pub fn test_parse_area() {
+    let example = read_string(15, true).unwrap();
+    let params = Params::new().with("row", 10).with("limit", 20);
+    let scan = Day15::parse_with(&example, &params).unwrap();
+    assert_eq!((scan.row, scan.limit, scan.sensors.len()), (10, 20, 14));
+
+    let err = Day15::parse_with(&format!("{}x", example), &params).unwrap_err();
+    assert_eq!(err.downcast_ref::<ParseError>().unwrap().line, Some(15));
+    assert!(Day15::parse_with(&example, &params.clone().with("column", 1)).is_err());
+    assert!(Day15::parse_with(&example, &Params::new().with("row", 1 << 40)).is_err());
+    assert!(Day15::parse_with(&example, &Params::new().with("limit", -1)).is_err());
+    // Without params even small inputs ask about the real puzzle's area.
+    let scan = Day15::parse(&example).unwrap();
+    assert_eq!((scan.row, scan.limit), (ROW, LIMIT));
+    assert_eq!(Day15::part1(&scan).unwrap(), Answer::from(0));
+
+    // The lone sensor leaves too much uncovered for part 2, but not part 1.
+    let lone = "Sensor at x=10, y=10: closest beacon is at x=10, y=11\n";
+    let scan = Day15::parse_with(lone, &params).unwrap();
+    assert_eq!(Day15::part1(&scan).unwrap(), Answer::from(3));
+    assert!(Day15::part2(&scan).is_err());
 }
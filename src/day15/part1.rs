@@ -1,62 +1,46 @@
 use std::collections::HashSet;
 
 use crate::{
-    day15::sensor::{parse_sensor, Sensor},
-    helper::read_lines,
+    day15::sensor::Sensor,
+    interval::{Interval, RangeSet},
+};
+#[cfg(test)]
+use crate::{
+    day15::Day15,
+    helper::read_string,
+    solution::{Answer, Solution},
 };
 
-// #[test]
+#[test]
 pub fn test_day15_pt_1() {
-    let lines: Vec<String> = read_lines(15, false);
-    let sensors: Vec<Sensor> = lines
-        .iter()
-        .map(|line| parse_sensor(line).unwrap().1)
-        .collect();
-    let size = setup_grid(&sensors);
-    println!("Part1 score is {:?}", size);
-    assert!(false);
+    let sensors = Day15::parse(&read_string(15, false).unwrap()).unwrap();
+    assert_eq!(Day15::part1(&sensors).unwrap(), Answer::from(4725496));
 }
 
-enum Cell {
-    Cover,
-    Empty,
-}
+/// Positions on `search_row` that are covered by a sensor but hold no beacon.
+pub fn setup_grid(sensors: &[Sensor], search_row: i32) -> u128 {
+    let covered = covered_on_row(sensors, search_row);
 
-fn setup_grid(sensors: &Vec<Sensor>) -> usize {
-    let search_row = 2_000_000;
-    let mut xs = HashSet::new();
-    for sensor in sensors {
-        let manhattan_distance = sensor.manhattan_distance;
-        if sensor.position.y <= search_row && sensor.position.y + manhattan_distance >= search_row {
-            let mut y = sensor.position.y + manhattan_distance;
-            let mut x_distance = 0;
-            while y >= search_row {
-                xs.insert(sensor.position.x + x_distance);
-                xs.insert(sensor.position.x - x_distance);
-                y = y - 1;
-                x_distance = x_distance + 1;
-            }
-        } else if sensor.position.y >= search_row
-            && sensor.position.y - manhattan_distance <= search_row
-        {
-            let mut y = sensor.position.y - manhattan_distance;
-            let mut x_distance = 0;
-            while y <= search_row {
-                xs.insert(sensor.position.x + x_distance);
-                xs.insert(sensor.position.x - x_distance);
-                y = y + 1;
-                x_distance = x_distance + 1;
-            }
-        }
-    }
+    let beacons: HashSet<i32> = sensors
+        .iter()
+        .map(|sensor| sensor.closest_beacon)
+        .filter(|beacon| beacon.y == search_row && covered.contains(beacon.x.into()))
+        .map(|beacon| beacon.x)
+        .collect();
 
-    for sensor in sensors {
-        if (sensor.position.y == search_row) {
-            xs.remove(&sensor.position.x);
-        } else if (sensor.closest_beacon.y == search_row) {
-            xs.remove(&sensor.closest_beacon.x);
-        }
-    }
+    covered.len() - beacons.len() as u128
+}
 
-    xs.len()
+/// The x positions on `row` within reach of a sensor.
+pub fn covered_on_row(sensors: &[Sensor], row: i32) -> RangeSet {
+    sensors
+        .iter()
+        .filter_map(|sensor| {
+            let reach = i64::from(sensor.manhattan_distance)
+                - (i64::from(sensor.position.y) - i64::from(row)).abs();
+            let x = i64::from(sensor.position.x);
+            // None when the row is out of reach.
+            Interval::new(x - reach, x + reach)
+        })
+        .collect()
 }
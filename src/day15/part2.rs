@@ -1,33 +1,71 @@
+use anyhow::{anyhow, Result};
+
+#[cfg(test)]
 use crate::{
-    day15::{
-        find_adjacent_sensors,
-        sensor::{parse_sensor, Sensor},
-    },
-    helper::read_lines,
+    day15::Day15,
+    helper::read_string,
+    solution::{Answer, Solution},
+};
+use crate::{
+    day15::{find_adjacent_sensors, part1::covered_on_row, sensor::Sensor},
+    interval::Interval,
+    point::Point,
+    trace,
 };
-
-use super::sensor::Position;
 
 #[test]
 pub fn test_day15_pt_2() {
-    let answer = day15_pt_2();
+    let sensors = Day15::parse(&read_string(15, false).unwrap()).unwrap();
+    assert_eq!(
+        Day15::part2(&sensors).unwrap(),
+        Answer::from(12_051_287_042_458i64)
+    );
+}
 
-    println!("Part2 score is {:?}", answer);
-    let tuning_frequency = (answer.x as i64) * 4_000_000 + answer.y as i64;
-    assert_eq!(tuning_frequency, 56_000_011);
+/// `x * 4000000 + y` of the one position in 0..=`limit` no sensor covers.
+pub fn tuning_frequency(sensors: &[Sensor], limit: i32) -> Result<i64> {
+    // The gap is usually between two pairs of sensors, but one on the edge of
+    // the area is only bounded by the edge.
+    let sensor_match = match find_adjacent_sensors(sensors, limit) {
+        Some(point) => point,
+        None => sweep_rows(sensors, limit)?,
+    };
+    trace::event!(Debug, "match found", point = sensor_match);
+    Ok((sensor_match.x as i64) * 4_000_000 + sensor_match.y as i64)
 }
 
-pub fn day15_pt_2() -> Position {
-    let lines: Vec<String> = read_lines(15, false);
-    let sensors: Vec<Sensor> = lines
-        .iter()
-        .map(|line| parse_sensor(line).unwrap().1)
-        .collect();
+/// Finds the uncovered position one row at a time, failing unless there is
+/// exactly one.
+fn sweep_rows(sensors: &[Sensor], limit: i32) -> Result<Point> {
+    let within = Interval::new(0, limit.into())
+        .ok_or_else(|| anyhow!("day 15: the limit {} is negative", limit))?;
+    let mut found = None;
+    for y in 0..=limit {
+        let gaps = covered_on_row(sensors, y).gaps(within);
+        match (found, gaps.len()) {
+            (_, 0) => {}
+            (None, 1) => found = Some(Point::new(gaps.intervals()[0].start() as i32, y)),
+            _ => {
+                return Err(anyhow!(
+                    "day 15: more than one position in 0..={} is uncovered",
+                    limit
+                ))
+            }
+        }
+    }
+    found.ok_or_else(|| anyhow!("day 15: every position in 0..={} is covered", limit))
+}
 
-    let sensor_match = find_adjacent_sensors(&sensors, 4_000_000);
-    println!("Match found {:?}", sensor_match);
-    return sensor_match;
-    // if let Some(value) = naive_scan(sensors) {
-    //     return value;
-    // }
+#[test]
+pub fn test_sweep_rows() {
+    let lone = Sensor::new(Point::new(10, 10), Point::new(10, 11)).unwrap();
+    let err = tuning_frequency(&[lone], 20).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "day 15: more than one position in 0..=20 is uncovered"
+    );
+    // Reaches everything but the corner at 20,20.
+    let corner = [Sensor::new(Point::new(0, 0), Point::new(0, 39)).unwrap()];
+    assert_eq!(tuning_frequency(&corner, 20).unwrap(), 80_000_020);
+    assert!(tuning_frequency(&corner, 19).is_err());
 }
@@ -1,42 +1,34 @@
-use std::{collections::HashSet, iter::FromFn, ops::Range};
+use std::{collections::HashSet, ops::Range};
 
 use nom::{
-    branch::alt,
-    bytes::complete::{tag, take_while},
-    character::complete::{self, digit1, newline, one_of},
-    combinator::{map, map_res},
-    error::{ErrorKind, ParseError},
-    multi::{separated_list0, separated_list1},
-    sequence::{delimited, pair, preceded, terminated, tuple},
-    IResult, Parser,
+    bytes::complete::tag,
+    sequence::{preceded, separated_pair},
+    Parser,
+};
+
+use crate::{
+    parse::{coordinates, IResult},
+    point::Point,
 };
 
 #[test]
 pub fn test_parse_sensor() {
     let input = r#"Sensor at x=20, y=1: closest beacon is at x=15, y=3"#;
-    let (_, sensor) = parse_sensor(input).unwrap();
-    assert!(sensor.position.x == 20);
-}
-
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub struct Position {
-    pub x: i32,
-    pub y: i32,
-}
-
-impl Position {
-    pub fn within(&self, lower: &Position, upper: &Position) -> bool {
-        if (self.x >= lower.x && self.x <= upper.x && self.y >= lower.y && self.y <= upper.y) {
-            return true;
-        }
-        false
-    }
+    let (_, (position, beacon)) = parse_sensor(input).unwrap();
+    assert!(position.x == 20);
+    assert_eq!(Sensor::new(position, beacon).unwrap().manhattan_distance, 7);
+
+    let far = Point::new(2_000_000_000, 0);
+    assert!(Sensor::new(far, -far).is_none());
+    // The reach ends at i32::MAX, the line just outside it does not fit.
+    let edge = Sensor::new(Point::new(0, 2_000_000_000), Point::new(0, i32::MAX));
+    assert!(edge.is_none());
 }
 
 #[derive(Debug, Clone)]
 pub struct Sensor {
-    pub position: Position,
-    pub closest_beacon: Position,
+    pub position: Point,
+    pub closest_beacon: Point,
     pub manhattan_distance: i32,
     pub x_range: (i32, i32),
     pub y_range: (i32, i32),
@@ -44,31 +36,32 @@ pub struct Sensor {
 }
 
 impl Sensor {
-    pub fn new(position: Position, closest_beacon: Position) -> Sensor {
-        let manhattan_distance = manhattan_distance(&position, &closest_beacon);
+    /// `None` when the reach or the lines around it do not fit in an `i32`.
+    pub fn new(position: Point, closest_beacon: Point) -> Option<Sensor> {
+        let manhattan_distance = position.checked_manhattan(closest_beacon)?;
         let x_range = (
-            position.x - manhattan_distance,
-            position.x + manhattan_distance,
+            position.x.checked_sub(manhattan_distance)?,
+            position.x.checked_add(manhattan_distance)?,
         );
         let y_range = (
-            position.y - manhattan_distance,
-            position.y + manhattan_distance,
+            position.y.checked_sub(manhattan_distance)?,
+            position.y.checked_add(manhattan_distance)?,
         );
 
-        Sensor {
+        Some(Sensor {
             position,
-            closest_beacon: closest_beacon,
+            closest_beacon,
             manhattan_distance,
             x_range,
             y_range,
-            lines: get_lines(manhattan_distance, &position),
-        }
+            lines: get_lines(manhattan_distance, &position)?,
+        })
     }
 
     pub fn search_grid(
         &self,
-        lower: Position,
-        upper: Position,
+        lower: Point,
+        upper: Point,
     ) -> impl Iterator<Item = (i32, Range<i32>)> + '_ {
         let distance = self.manhattan_distance;
         let row = self.position.y - distance;
@@ -105,35 +98,22 @@ impl Sensor {
         top_iterator.chain(bottom_iterator)
     }
 
-    pub fn within_bounds(&self, lower: &Position, upper: &Position) -> bool {
-        if (self.x_range.0 <= lower.x && self.x_range.1 >= lower.x)
-            || (self.x_range.0 >= lower.x && self.x_range.0 <= upper.x)
-        {
-            if (self.y_range.0 <= lower.y && self.y_range.1 >= lower.y)
-                || (self.y_range.0 >= lower.y && self.y_range.0 <= upper.y)
-            {
-                return true;
-            }
-        }
-        return false;
+    pub fn within_bounds(&self, lower: &Point, upper: &Point) -> bool {
+        ((self.x_range.0 <= lower.x && self.x_range.1 >= lower.x)
+            || (self.x_range.0 >= lower.x && self.x_range.0 <= upper.x))
+            && ((self.y_range.0 <= lower.y && self.y_range.1 >= lower.y)
+                || (self.y_range.0 >= lower.y && self.y_range.0 <= upper.y))
     }
 
     pub fn intersects(&self, other: &Sensor) -> bool {
-        if self.position != other.position {
-            let this_slope = self.lines;
-            let other_slope = other.lines;
-            if !HashSet::from(this_slope)
-                .intersection(&HashSet::from(other_slope))
-                .collect::<Vec<&(i32, i32)>>()
-                .is_empty()
-            {
-                return true;
-            }
-        }
-        return false;
+        self.position != other.position
+            && HashSet::from(self.lines)
+                .intersection(&HashSet::from(other.lines))
+                .next()
+                .is_some()
     }
 
-    pub fn line_points(&self) -> impl Iterator<Item = Position> + '_ {
+    pub fn line_points(&self) -> impl Iterator<Item = Point> + '_ {
         let distance = self.manhattan_distance;
         let mut x = self.position.x - distance - 1;
         let mut y = self.position.y;
@@ -168,21 +148,16 @@ impl Sensor {
                 _ => {}
             }
 
-            return Some(Position { x, y });
+            Some(Point { x, y })
         })
     }
 
-    pub fn contains(&self, pos: &Position) -> bool {
+    pub fn contains(&self, pos: &Point) -> bool {
         let lines = self.lines;
-        if lines[0].0 * pos.x + lines[0].1 < pos.y
+        lines[0].0 * pos.x + lines[0].1 < pos.y
             && lines[1].0 * pos.x + lines[1].1 > pos.y
             && lines[2].0 * pos.x + lines[2].1 > pos.y
             && lines[3].0 * pos.x + lines[3].1 < pos.y
-        {
-            return true;
-        }
-
-        return false;
     }
 }
 
@@ -195,99 +170,89 @@ enum Side {
     Done,
 }
 
-fn manhattan_distance(position: &Position, beacon: &Position) -> i32 {
-    (position.x.abs_diff(beacon.x) + position.y.abs_diff(beacon.y)) as i32
-}
-
 #[test]
 pub fn test_slope() {
-    let sensor_1 = Sensor::new(Position { x: 5, y: 5 }, Position { x: 5, y: 1 });
+    let sensor_1 = Sensor::new(Point { x: 5, y: 5 }, Point { x: 5, y: 1 }).unwrap();
 
-    println!("{:?}", sensor_1.lines);
+    assert_eq!(sensor_1.lines, [(-1, 5), (-1, 15), (1, 5), (1, -5)]);
 }
 
-fn get_lines(manhattan_distance: i32, position: &Position) -> [(i32, i32); 4] {
-    let mut slopes = [(0, 0); 4];
-    let distance = manhattan_distance;
+/// The slope and offset of the lines just outside each side of the reach,
+/// worked out in `i64` and `None` when one does not fit back in an `i32`.
+fn get_lines(manhattan_distance: i32, position: &Point) -> Option<[(i32, i32); 4]> {
+    let (x, y) = (i64::from(position.x), i64::from(position.y));
+    let distance = i64::from(manhattan_distance);
     let k = -1;
-    let m = position.y - (distance + 1) - k * position.x;
-    slopes[0] = (k, m);
-    slopes[1] = (k, m + (distance + 1) * 2);
+    let m = y - (distance + 1) - k * x;
+    let slopes_left = [(k, m), (k, m + (distance + 1) * 2)];
     let k = 1;
-    let m = (position.y + (distance + 1)) - k * position.x;
-    slopes[2] = (k, m);
-    slopes[3] = (k, m - (distance + 1) * 2);
-    slopes
+    let m = (y + (distance + 1)) - k * x;
+    let slopes_right = [(k, m), (k, m - (distance + 1) * 2)];
+
+    let mut slopes = [(0, 0); 4];
+    for (slope, (k, m)) in slopes
+        .iter_mut()
+        .zip(slopes_left.into_iter().chain(slopes_right))
+    {
+        *slope = (k as i32, i32::try_from(m).ok()?);
+    }
+    Some(slopes)
 }
 
 #[test]
 pub fn test_search_grid() {
-    let sensor = Sensor::new(Position { x: 8, y: 7 }, Position { x: 2, y: 10 });
+    let sensor = Sensor::new(Point { x: 10, y: 10 }, Point { x: 4, y: 13 }).unwrap();
 
     let mut count = 0;
     let mut grid = vec![vec![false; 20]; 20];
-    for (row, range) in sensor.search_grid(Position { x: 0, y: 0 }, Position { x: 100, y: 100 }) {
+    for (row, range) in sensor.search_grid(Point { x: 0, y: 0 }, Point { x: 100, y: 100 }) {
         for x in range {
-            println!("{} {}", row, x);
             grid[row as usize][x as usize] = true;
             count += 1;
         }
     }
 
-    print_grid(&grid);
-
+    // The sensor's whole diamond, 9 out from (10, 10).
     assert_eq!(count, 181);
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &cell) in row.iter().enumerate() {
+            let point = Point::new(x as i32, y as i32);
+            assert_eq!(cell, sensor.position.manhattan(point) <= 9, "{:?}", point);
+        }
+    }
 }
 
 #[test]
 pub fn test_search_grid2() {
-    let sensor = Sensor::new(Position { x: 20, y: 14 }, Position { x: 25, y: 17 });
+    let sensor = Sensor::new(Point { x: 20, y: 14 }, Point { x: 25, y: 17 }).unwrap();
 
     let mut count = 0;
-    let mut grid = vec![vec![true; 5]; 5];
-    for (row, range) in sensor.search_grid(Position { x: 15, y: 15 }, Position { x: 20, y: 20 }) {
+    let mut grid = vec![vec![false; 6]; 6];
+    for (row, range) in sensor.search_grid(Point { x: 15, y: 15 }, Point { x: 20, y: 20 }) {
         for x in range {
-            println!("{} {}", row, x);
             grid[(row - 15) as usize][(x - 15) as usize] = true;
             count += 1;
         }
     }
 
-    print_grid(&grid);
-
-    assert_eq!(count, 181);
-}
-
-pub fn print_grid(grid: &Vec<Vec<bool>>) {
-    for (i, row) in grid.iter().enumerate() {
-        let mut line = "".to_owned();
-        line.push_str(&format!("Row: {: ^2}", i.to_string()));
-        for col in row {
-            line.push(if !*col { '#' } else { ' ' });
-        }
-        println!("{}", line);
-    }
+    // Rows 15 to 20 of the diamond, cut off at x=15: three rows of 6, then 5,
+    // 4 and 3 towards the bottom tip.
+    assert_eq!(count, 30);
+    let widths: Vec<usize> = grid
+        .iter()
+        .map(|row| row.iter().filter(|&&cell| cell).count())
+        .collect();
+    assert_eq!(widths, [6, 6, 6, 5, 4, 3]);
+    assert!(grid.iter().all(|row| row[5]));
 }
 
-pub fn parse_sensor(input: &str) -> IResult<&str, Sensor> {
-    map(
-        tuple((
-            preceded(tag("Sensor at x="), complete::i32),
-            preceded(tag(", y="), complete::i32),
-            preceded(tag(": closest beacon is at x="), complete::i32),
-            preceded(tag(", y="), complete::i32),
-        )),
-        |(sensor_x, sensor_y, beacon_x, beacon_y)| {
-            Sensor::new(
-                Position {
-                    x: sensor_x,
-                    y: sensor_y,
-                },
-                Position {
-                    x: beacon_x,
-                    y: beacon_y,
-                },
-            )
-        },
-    )(input)
+/// `Sensor at x=2, y=18: closest beacon is at x=-2, y=15`, as the sensor's
+/// position and its beacon's.
+pub fn parse_sensor(input: &str) -> IResult<'_, (Point, Point)> {
+    separated_pair(
+        preceded(tag("Sensor at "), coordinates),
+        tag(": closest beacon is at "),
+        coordinates,
+    )
+    .parse(input)
 }
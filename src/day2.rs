@@ -1,6 +1,13 @@
 use std::str::FromStr;
 
-use crate::helper::{read_lines, ParseError};
+use anyhow::Result;
+
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::{parse_lines, ParseError},
+    solution::{solution_api, Answer, Solution},
+};
 
 #[derive(Clone, Copy, Debug)]
 enum ScissorHand {
@@ -22,7 +29,7 @@ impl FromStr for ScissorHand {
             "X" | "A" => Ok(ScissorHand::Rock),
             "Y" | "B" => Ok(ScissorHand::Paper),
             "Z" | "C" => Ok(ScissorHand::Scissors),
-            _ => Err(ParseError::new("Not part of enum")),
+            _ => Err(ParseError::new("expected one of A, B, C, X, Y or Z", s)),
         }
     }
 
@@ -47,65 +54,97 @@ impl FromStr for Outcome {
             "X" => Ok(Outcome::Loose),
             "Y" => Ok(Outcome::Draw),
             "Z" => Ok(Outcome::Win),
-            _ => Err(ParseError::new("Not part of enum")),
+            _ => Err(ParseError::new("expected one of X, Y or Z", s)),
         }
     }
 
     type Err = ParseError;
 }
 
-#[test]
-pub fn test_day2_pt_1() {
-    let strategy: Vec<String> = read_lines(2, false);
-    let plays: Vec<(ScissorHand, ScissorHand)> = parse_strategy(strategy, part1_strategy);
+/// One line of the strategy guide, with the second column read both ways.
+#[derive(Clone, Copy, Debug)]
+pub struct Round {
+    other: ScissorHand,
+    hand: ScissorHand,
+    outcome: Outcome,
+}
+
+pub struct Day2;
 
-    let score: u32 = plays.iter().map(calculate_score).sum();
+impl Solution for Day2 {
+    type Input = Vec<Round>;
 
-    println!("Part1 score is {:?}", score);
-    assert!(false);
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(2, input, parse_round)?)
+    }
+
+    fn part1(rounds: &Self::Input) -> Result<Answer> {
+        Ok(score(rounds, part1_strategy).into())
+    }
+
+    fn part2(rounds: &Self::Input) -> Result<Answer> {
+        Ok(score(rounds, part2_strategy).into())
+    }
+}
+
+solution_api!(Day2);
+
+#[test]
+pub fn test_day2_pt_1() {
+    let rounds = Day2::parse(&read_string(2, false).unwrap()).unwrap();
+    assert_eq!(Day2::part1(&rounds).unwrap(), Answer::from(13675));
 }
 
 #[test]
 pub fn test_day2_pt_2() {
-    let strategy: Vec<String> = read_lines(2, false);
-    let plays: Vec<(ScissorHand, ScissorHand)> = parse_strategy(strategy, part2_strategy);
+    let rounds = Day2::parse(&read_string(2, false).unwrap()).unwrap();
+    assert_eq!(Day2::part2(&rounds).unwrap(), Answer::from(14184));
+}
 
-    let score: u32 = plays.iter().map(calculate_score).sum();
+fn parse_round(line: &str) -> Result<Round, ParseError> {
+    let (other, suggestion) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new("expected two columns", line))?;
+    let suggestion_column = other.len() + 2;
+    Ok(Round {
+        other: other
+            .parse::<ScissorHand>()
+            .map_err(|err| err.at_column(1))?,
+        hand: suggestion
+            .parse::<ScissorHand>()
+            .map_err(|err| err.at_column(suggestion_column))?,
+        outcome: suggestion
+            .parse::<Outcome>()
+            .map_err(|err| err.at_column(suggestion_column))?,
+    })
+}
 
-    println!("Part1 score is {:?}", score);
-    assert!(false);
+#[test]
+pub fn test_parse_round() {
+    let err = Day2::parse("A Y\nB W\n").unwrap_err();
+    let err = err.downcast::<ParseError>().unwrap();
+    assert_eq!((err.line, err.column), (Some(2), Some(3)));
+    assert_eq!(err.text, "W");
 }
 
-fn parse_strategy(
-    strategy: Vec<String>,
-    choose_hand: fn(ScissorHand, &str) -> ScissorHand,
-) -> Vec<(ScissorHand, ScissorHand)> {
-    strategy
+fn score(rounds: &[Round], choose_hand: fn(&Round) -> ScissorHand) -> u32 {
+    rounds
         .iter()
-        .map(|s| {
-            let inputs: Vec<&str> = s.split(" ").collect();
-            let other = get_strategy(inputs[0]);
-            let suggestion = choose_hand(other, inputs[1]);
-            (other, suggestion)
-        })
-        .collect()
+        .map(|round| calculate_score(&(round.other, choose_hand(round))))
+        .sum()
 }
 
-fn part2_strategy(other: ScissorHand, hand: &str) -> ScissorHand {
-    match hand.parse::<Outcome>() {
-        Ok(Outcome::Win) => ((other as u8 + 1) % 3).into(),
-        Ok(Outcome::Draw) => other,
-        Ok(Outcome::Loose) => ((other as i8 - 1).abs() as u8).into(),
-        Err(_) => panic!("Could not parse hand"),
+fn part2_strategy(round: &Round) -> ScissorHand {
+    let other = round.other;
+    match round.outcome {
+        Outcome::Win => ((other as u8 + 1) % 3).into(),
+        Outcome::Draw => other,
+        Outcome::Loose => (other as i8 - 1).unsigned_abs().into(),
     }
 }
 
-fn part1_strategy(other: ScissorHand, hand: &str) -> ScissorHand {
-    hand.parse::<ScissorHand>().unwrap()
-}
-
-fn get_strategy(hand: &str) -> ScissorHand {
-    hand.parse::<ScissorHand>().unwrap()
+fn part1_strategy(round: &Round) -> ScissorHand {
+    round.hand
 }
 
 fn calculate_score(hands: &(ScissorHand, ScissorHand)) -> u32 {
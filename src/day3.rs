@@ -1,31 +1,75 @@
-use std::{collections::HashSet, str::FromStr};
+use std::collections::HashSet;
 
-use crate::helper::{read_lines, ParseError};
+use anyhow::Result;
+
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::{parse_lines, ParseError},
+    solution::{solution_api, Answer, Solution},
+};
 
 #[test]
 pub fn ascii_code() {
     assert_eq!(to_ascii_value(&'p'), 16);
 }
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(3, input, parse_rucksack)?)
+    }
+
+    fn part1(rucksacks: &Self::Input) -> Result<Answer> {
+        let common_items: Vec<char> = rucksacks
+            .iter()
+            .map(|row| {
+                let (first, second) = row.split_at(row.len() / 2);
+                let first_set: HashSet<char> = HashSet::from_iter(first.chars());
+                let second_set: HashSet<char> = HashSet::from_iter(second.chars());
+
+                let common: Vec<&char> = first_set.intersection(&second_set).collect();
+                assert_eq!(common.len(), 1);
+                *common[0]
+            })
+            .collect();
+
+        let scores: Vec<u32> = common_items.iter().map(to_ascii_value).collect();
+        Ok(scores.iter().sum::<u32>().into())
+    }
+
+    fn part2(rucksacks: &Self::Input) -> Result<Answer> {
+        let elve_groups = ElveGroup::new(rucksacks.clone());
+        let common_items: Vec<char> = elve_groups
+            .map(|(first, second, third)| {
+                let first_set: HashSet<char> = HashSet::from_iter(first.chars());
+                let second_set: HashSet<char> = HashSet::from_iter(second.chars());
+                let third_set: HashSet<char> = HashSet::from_iter(third.chars());
+
+                let common_set: HashSet<char> =
+                    HashSet::from_iter(first_set.intersection(&second_set).copied());
+                let common_set = common_set.intersection(&third_set);
+
+                let common: Vec<&char> = common_set.collect();
+                assert_eq!(common.len(), 1);
+                *common[0]
+            })
+            .collect();
+
+        let scores: Vec<u32> = common_items.iter().map(to_ascii_value).collect();
+        Ok(scores.iter().sum::<u32>().into())
+    }
+}
+
+solution_api!(Day3);
+
 #[test]
 pub fn test_day3_pt_1() {
-    let rucksacks: Vec<String> = read_lines(3, false);
-    let common_items: Vec<char> = rucksacks
-        .iter()
-        .map(|row| {
-            let (first, second) = row.split_at(row.len() / 2);
-            let first_set: HashSet<char> = HashSet::from_iter(first.chars());
-            let second_set: HashSet<char> = HashSet::from_iter(second.chars());
-
-            let common: Vec<&char> = first_set.intersection(&second_set).collect();
-            assert_eq!(common.len(), 1);
-            return common[0].clone();
-        })
-        .collect();
-
-    let scores: Vec<u32> = common_items.iter().map(to_ascii_value).collect();
-
-    println!("Part1 score is {:?}", scores.iter().sum::<u32>());
-    assert!(false);
+    let rucksacks = Day3::parse(&read_string(3, false).unwrap()).unwrap();
+    assert_eq!(Day3::part1(&rucksacks).unwrap(), Answer::from(8394));
 }
 
 struct ElveGroup {
@@ -74,29 +118,21 @@ impl Iterator for ElveGroup {
 
 #[test]
 pub fn test_day3_pt_2() {
-    let rucksacks: Vec<String> = read_lines(3, false);
-    let elve_groups = ElveGroup::new(rucksacks);
-    let common_items: Vec<char> = elve_groups
-        .map(|(first, second, third)| {
-            let first_set: HashSet<char> = HashSet::from_iter(first.chars());
-            let second_set: HashSet<char> = HashSet::from_iter(second.chars());
-            let third_set: HashSet<char> = HashSet::from_iter(third.chars());
-
-            let common_set: HashSet<char> =
-                HashSet::from_iter(first_set.intersection(&second_set).copied());
-            let common_set = common_set.intersection(&third_set);
-
-            let common: Vec<&char> = common_set.collect();
-            println!("Common items {:?}", common);
-            assert_eq!(common.len(), 1);
-            return common[0].clone();
-        })
-        .collect();
-
-    let scores: Vec<u32> = common_items.iter().map(to_ascii_value).collect();
-
-    println!("Part1 score is {:?}", scores.iter().sum::<u32>());
-    assert!(false);
+    let rucksacks = Day3::parse(&read_string(3, false).unwrap()).unwrap();
+    assert_eq!(Day3::part2(&rucksacks).unwrap(), Answer::from(2413));
+}
+
+fn parse_rucksack(line: &str) -> Result<String, ParseError> {
+    if let Some(column) = line.find(|c: char| !c.is_ascii_alphabetic()) {
+        return Err(ParseError::new("expected an item a-z or A-Z", line).at_column(column + 1));
+    }
+    if !line.len().is_multiple_of(2) {
+        return Err(ParseError::new(
+            "expected two equally sized compartments",
+            line,
+        ));
+    }
+    Ok(line.to_owned())
 }
 
 fn to_ascii_value(c: &char) -> u32 {
@@ -1,83 +1,116 @@
-use std::{collections::HashSet, hash::Hash, str::FromStr};
+use std::collections::HashSet;
 
-use crate::helper::{read_lines, ParseError};
+use anyhow::Result;
 
-fn parse_range(range_str: &str) -> HashSet<usize> {
-    let (from, to) = range_str.split_once("-").unwrap();
-    let from = from.parse::<usize>().unwrap();
-    let to = to.parse::<usize>().unwrap();
-    let range: Vec<usize> = (from..to + 1).collect();
-    HashSet::from_iter(range)
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::{parse_lines, ParseError},
+    interval::Interval,
+    solution::{solution_api, Answer, Params, Part, Solution},
+};
+
+type Assignment = (Interval, Interval);
+
+fn parse_range(range_str: &str) -> Result<Interval, ParseError> {
+    let (from, to) = range_str
+        .split_once('-')
+        .ok_or_else(|| ParseError::new("expected a range like 2-4", range_str))?;
+    let from = from
+        .parse::<u32>()
+        .map_err(|_| ParseError::new("expected a section id", from).at_column(1))?;
+    let to_column = range_str.len() - to.len() + 1;
+    let to = to
+        .parse::<u32>()
+        .map_err(|_| ParseError::new("expected a section id", to).at_column(to_column))?;
+    Interval::new(from.into(), to.into())
+        .ok_or_else(|| ParseError::new("expected the range to end after it starts", range_str))
+}
+
+fn parse_assignment(row: &str) -> Result<Assignment, ParseError> {
+    let (first, second) = row
+        .split_once(',')
+        .ok_or_else(|| ParseError::new("expected two comma separated ranges", row))?;
+    let first_set = parse_range(first)?;
+    let second_set =
+        parse_range(second).map_err(|err| err.at_column(1).shifted(first.len() + 1))?;
+    Ok((first_set, second_set))
 }
 
 #[test]
 pub fn test_range() {
-    let range = parse_range("53-53");
-    assert!(range.contains(&53));
+    let range = parse_range("53-53").unwrap();
+    assert!(range.contains(53));
 }
 
-fn parse_from_to(range_str: &str) -> (usize, usize) {
-    let (from, to) = range_str.split_once("-").unwrap();
-    let from = from.parse::<usize>().unwrap();
-    let to = to.parse::<usize>().unwrap();
-    (from, to)
+#[test]
+pub fn test_parse_assignment() {
+    let err = Day4::parse("2-4,6-8\n2-3,4-x\n").unwrap_err();
+    let err = err.downcast::<ParseError>().unwrap();
+    assert_eq!((err.line, err.column), (Some(2), Some(7)));
+    assert_eq!(err.text, "x");
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Assignment>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_lines(4, input, parse_assignment)?)
+    }
+
+    fn part1(assignments: &Self::Input) -> Result<Answer> {
+        Ok(assignments
+            .iter()
+            .filter(|sets| fully_contains(sets))
+            .count()
+            .into())
+    }
+
+    fn part2(assignments: &Self::Input) -> Result<Answer> {
+        Ok(assignments
+            .iter()
+            .filter(|sets| overlaps(sets))
+            .count()
+            .into())
+    }
+}
+
+solution_api!(Day4);
+
 #[test]
 pub fn test_day4_pt_1() {
-    let assignments: Vec<String> = read_lines(4, false);
-    let common_items: usize = assignments
-        .iter()
-        .map(|row| {
-            let (first, second) = row.split_once(",").unwrap();
-            let first_set = parse_range(first);
-            let second_set = parse_range(second);
-            (first_set, second_set)
-        })
-        .filter(fully_contains)
-        .count();
-
-    println!("Part1 score is {:?}", common_items);
-    assert!(false);
+    let assignments = Day4::parse(&read_string(4, false).unwrap()).unwrap();
+    assert_eq!(Day4::part1(&assignments).unwrap(), Answer::from(424));
 }
 #[test]
 pub fn test_day4_pt_2() {
-    let assignments: Vec<String> = read_lines(4, false);
-    let common_items: usize = assignments
-        .iter()
-        .map(|row| {
-            let (first, second) = row.split_once(",").unwrap();
-            let first_set = parse_range(first);
-            let second_set = parse_range(second);
-            (first_set, second_set)
-        })
-        .filter(overlaps)
-        .count();
-
-    println!("Part1 score is {:?}", common_items);
-    assert!(false);
+    let assignments = Day4::parse(&read_string(4, false).unwrap()).unwrap();
+    assert_eq!(Day4::part2(&assignments).unwrap(), Answer::from(804));
 }
 
-fn overlaps(sets: &(HashSet<usize>, HashSet<usize>)) -> bool {
-    let (first_set, second_set) = sets;
-    if (first_set.intersection(&second_set).count() > 0) {
-        return true;
-    } else {
-        return false;
-    }
+fn overlaps((first, second): &Assignment) -> bool {
+    first.overlaps(second)
 }
-fn fully_contains(sets: &(HashSet<usize>, HashSet<usize>)) -> bool {
-    let (first_set, second_set) = sets;
-    if (first_set.is_superset(&second_set) || second_set.is_superset(&first_set)) {
-        return true;
-    } else {
-        return false;
-    }
+fn fully_contains((first, second): &Assignment) -> bool {
+    first.contains_interval(second) || second.contains_interval(first)
 }
-// #[test]
-// pub fn test_day4_pt_2() {
-//     let rucksacks: Vec<String> = read_lines(4, false);
 
-//     println!("Part1 score is {:?}", scores.iter().sum::<u32>());
-//     assert!(false);
-// }
+/// Every section of both ranges in a set, for differential testing against
+/// the interval arithmetic above.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub(crate) fn brute_force(input: &str, params: &Params, part: Part) -> Result<Answer> {
+    let sections = |range: &Interval| (range.start()..=range.end()).collect::<HashSet<i64>>();
+    let count = Day4::parse_with(input, params)?
+        .iter()
+        .filter(|(first, second)| {
+            let (first, second) = (sections(first), sections(second));
+            match part {
+                Part::One => first.is_subset(&second) || second.is_subset(&first),
+                Part::Two => !first.is_disjoint(&second),
+            }
+        })
+        .count();
+    Ok(count.into())
+}
@@ -1,116 +1,157 @@
-use std::{
-    collections::{HashMap, HashSet},
-    hash::Hash,
-    str::FromStr,
-};
+use std::{collections::HashMap, str::FromStr};
 
+use anyhow::{anyhow, Result};
 use regex::Regex;
 
-use crate::helper::{read_lines, ParseError};
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::ParseError,
+    solution::{solution_api, Answer, Solution},
+};
 
-#[derive(Debug)]
-struct Crates {
+#[derive(Debug, Clone)]
+pub struct Crates {
     pub stacks: HashMap<u8, Vec<char>>,
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = (Crates, Vec<Movement>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let lines: Vec<&str> = input.lines().collect();
+        let mut placement: Vec<&str> = lines
+            .iter()
+            .copied()
+            .take_while(|line| !line.is_empty())
+            .collect();
+        placement.reverse();
+        let crates = parse_placement_into_crates(&placement).map_err(|err| err.for_day(5))?;
+
+        let first_movement = placement.len() + 2;
+        let mut heights: HashMap<u8, usize> = crates
+            .stacks
+            .iter()
+            .map(|(&number, stack)| (number, stack.len()))
+            .collect();
+        let movements = lines
+            .iter()
+            .skip(placement.len() + 1)
+            .enumerate()
+            .map(|(i, line)| {
+                parse_checked_move(line, &mut heights)
+                    .map_err(|err| err.at_line(first_movement + i).for_day(5))
+            })
+            .collect::<Result<Vec<Movement>, ParseError>>()?;
+
+        Ok((crates, movements))
+    }
+
+    fn part1((crates, movements): &Self::Input) -> Result<Answer> {
+        let mut crates = crates.clone();
+
+        for movement in movements {
+            for _ in 0..movement.num_containers {
+                let container = take_crate(&mut crates, movement)?;
+                stack(&mut crates, movement.to)?.push(container);
+            }
+        }
+        Ok(get_top_container(crates).into())
+    }
+
+    fn part2((crates, movements): &Self::Input) -> Result<Answer> {
+        let mut crates = crates.clone();
+
+        for movement in movements {
+            let mut temp_stack: Vec<char> = Vec::new();
+            for _ in 0..movement.num_containers {
+                temp_stack.push(take_crate(&mut crates, movement)?);
+            }
+
+            let to_stack = stack(&mut crates, movement.to)?;
+            for moved_container in temp_stack.iter().rev() {
+                to_stack.push(*moved_container);
+            }
+        }
+        Ok(get_top_container(crates).into())
+    }
+}
+
+solution_api!(Day5);
+
+fn stack(crates: &mut Crates, number: u8) -> Result<&mut Vec<char>> {
+    crates
+        .stacks
+        .get_mut(&number)
+        .ok_or_else(|| anyhow!("day 5: there is no stack {}", number))
+}
+
+fn take_crate(crates: &mut Crates, movement: &Movement) -> Result<char> {
+    stack(crates, movement.from)?
+        .pop()
+        .ok_or_else(|| anyhow!("day 5: stack {} ran out of crates", movement.from))
+}
+
 #[test]
 pub fn test_day5_pt_1() {
-    let lines: Vec<String> = read_lines(5, false);
-    let mut placement: Vec<&String> = lines.iter().take_while(|line| !line.is_empty()).collect();
-    placement.reverse();
-    dbg!(&placement);
-    let movements = lines.iter().skip(placement.len() + 1);
-
-    let mut crates = parse_placement_into_crates(placement);
-
-    for movement in movements {
-        let movement = movement.parse::<Movement>().unwrap();
-
-        (0..movement.num_containers).for_each(|_| {
-            let from_stack = crates.stacks.get_mut(&movement.from).unwrap();
-            let container = from_stack.pop().unwrap();
-            let to_stack = crates.stacks.get_mut(&movement.to).unwrap();
-            to_stack.push(container);
-        });
-    }
-    let top = get_top_container(crates);
-    println!("Part1 score is {:?}", &top);
-    assert!(false);
+    let input = Day5::parse(&read_string(5, false).unwrap()).unwrap();
+    assert_eq!(Day5::part1(&input).unwrap(), Answer::from("SBPQRSCDF"));
 }
 
-fn parse_placement_into_crates(placement: Vec<&String>) -> Crates {
+/// Reads the crate drawing bottom up, `placement[0]` being the stack numbers.
+fn parse_placement_into_crates(placement: &[&str]) -> Result<Crates, ParseError> {
+    let numbers = placement
+        .first()
+        .ok_or_else(|| ParseError::new("expected a drawing of the stacks", "").at_line(1))?;
+    let numbers_line = placement.len();
+    let num_stacks = numbers
+        .split_whitespace()
+        .last()
+        .and_then(|last| last.parse::<u8>().ok())
+        .ok_or_else(|| ParseError::new("expected stack numbers", numbers).at_line(numbers_line))?;
+
     let mut crates = Crates {
-        stacks: HashMap::new(),
+        stacks: (1..=num_stacks).map(|stack| (stack, Vec::new())).collect(),
     };
-    let line_len = placement[0].len();
-    let num_stacks = placement[0]
-        .chars()
-        .nth(line_len - 2)
-        .unwrap()
-        .to_digit(10)
-        .unwrap() as usize;
-    for line in placement.iter().skip(1) {
+    for (i, line) in placement.iter().enumerate().skip(1) {
         let chars: Vec<char> = line.chars().collect();
-        for stack in 1..num_stacks + 1 {
-            let container = chars[(stack - 1) * 4 + 1];
+        for stack in 1..=num_stacks {
+            let column = (stack as usize - 1) * 4 + 1;
+            let container = chars.get(column).copied().unwrap_or(' ');
             if container.is_whitespace() {
                 continue;
             }
-            if let Some(entry) = crates.stacks.get_mut(&(stack as u8)) {
-                entry.push(container);
-            } else {
-                crates.stacks.insert(stack as u8, vec![container]);
+            if !container.is_ascii_uppercase() {
+                return Err(ParseError::new("expected a crate like [A]", line)
+                    .at_line(numbers_line - i)
+                    .at_column(column + 1));
             }
+            crates.stacks.entry(stack).or_default().push(container);
         }
     }
-    crates
+    Ok(crates)
 }
 
 #[test]
 pub fn test_day5_pt_2() {
-    let lines: Vec<String> = read_lines(5, false);
-    let mut placement: Vec<&String> = lines.iter().take_while(|line| !line.is_empty()).collect();
-    placement.reverse();
-    let movements = lines.iter().skip(placement.len() + 1);
-
-    let mut crates = parse_placement_into_crates(placement);
-
-    for movement in movements {
-        let movement = movement.parse::<Movement>().unwrap();
-
-        let mut temp_stack: Vec<char> = Vec::new();
-        (0..movement.num_containers).for_each(|_| {
-            let from_stack = crates.stacks.get_mut(&movement.from).unwrap();
-            let container = from_stack.pop().unwrap();
-            temp_stack.push(container);
-        });
-
-        for moved_container in temp_stack.iter().rev() {
-            let to_stack = crates.stacks.get_mut(&movement.to).unwrap();
-            to_stack.push(*moved_container);
-        }
-    }
-    let top = get_top_container(crates);
-    println!("Part2 score is {:?}", top);
-    assert!(false);
+    let input = Day5::parse(&read_string(5, false).unwrap()).unwrap();
+    assert_eq!(Day5::part2(&input).unwrap(), Answer::from("RGLVRCQSB"));
 }
 
 fn get_top_container(crates: Crates) -> String {
     let mut top = "".to_string();
     for i in 0..crates.stacks.len() {
-        let top_container = crates
-            .stacks
-            .get(&((i + 1) as u8))
-            .unwrap()
-            .last()
-            .unwrap()
-            .to_string();
-        top.push_str(&top_container);
+        if let Some(top_container) = crates.stacks.get(&((i + 1) as u8)).unwrap().last() {
+            top.push(*top_container);
+        }
     }
     top
 }
 
-struct Movement {
+#[derive(Debug)]
+pub struct Movement {
     from: u8,
     to: u8,
     num_containers: u8,
@@ -120,27 +161,87 @@ impl FromStr for Movement {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(parse_move(s))
+        parse_move(s)
     }
 }
-fn parse_move(movement: &str) -> Movement {
-    let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
-    let matches = re.captures(movement).unwrap();
-
-    let from = matches.get(2).unwrap().as_str().parse::<u8>().unwrap();
-    let to = matches.get(3).unwrap().as_str().parse::<u8>().unwrap();
-    let num_containers = matches.get(1).unwrap().as_str().parse::<u8>().unwrap();
-    Movement {
-        from,
-        to,
-        num_containers,
+fn parse_move(movement: &str) -> Result<Movement, ParseError> {
+    let re = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
+    let matches = re
+        .captures(movement)
+        .ok_or_else(|| ParseError::new("expected move N from N to N", movement))?;
+
+    let number = |group: usize| {
+        let capture = matches.get(group).unwrap();
+        capture.as_str().parse::<u8>().map_err(|_| {
+            ParseError::new("expected a number below 256", capture.as_str())
+                .at_column(capture.start() + 1)
+        })
+    };
+    Ok(Movement {
+        from: number(2)?,
+        to: number(3)?,
+        num_containers: number(1)?,
+    })
+}
+
+/// Checks the move against the stack `heights`, and moves the crates there.
+fn parse_checked_move(
+    line: &str,
+    heights: &mut HashMap<u8, usize>,
+) -> Result<Movement, ParseError> {
+    let movement = line.parse::<Movement>()?;
+    if !heights.contains_key(&movement.from) || !heights.contains_key(&movement.to) {
+        return Err(ParseError::new("expected an existing stack", line));
     }
+    let count = usize::from(movement.num_containers);
+    let from = heights.get_mut(&movement.from).unwrap();
+    if *from < count {
+        let message = format!("expected stack {} to hold {} crates", movement.from, count);
+        return Err(ParseError::new(&message, line));
+    }
+    *from -= count;
+    *heights.get_mut(&movement.to).unwrap() += count;
+    Ok(movement)
 }
+
 #[test]
 pub fn test_movement() {
     let movement_str = "move 1 from 2 to 1".to_owned();
-    let movement = parse_move(&movement_str);
+    let movement = parse_move(&movement_str).unwrap();
     assert_eq!(movement.from, 2);
     assert_eq!(movement.to, 1);
     assert_eq!(movement.num_containers, 1);
 }
+
+#[test]
+pub fn test_day5_parse_error() {
+    let input = "    [D]\n[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 1 from 300 to 1\n";
+    let err = Day5::parse(input).unwrap_err();
+    let err = err.downcast::<ParseError>().unwrap();
+    assert_eq!((err.line, err.column), (Some(6), Some(13)));
+
+    let input = "    [D]\n[N] [C]\n 1   2\n\nmove 2 from 2 to 1\nmove 4 from 1 to 2\n";
+    let err = Day5::parse(input).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "day 5, line 6: expected stack 1 to hold 4 crates: \"move 4 from 1 to 2\""
+    );
+    assert!(Day5::parse(&input.replace("move 4", "move 3")).is_ok());
+
+    // Moves built without parse are not checked up front.
+    let crates = Crates {
+        stacks: HashMap::from([(1, vec!['A'])]),
+    };
+    let movement = Movement {
+        from: 1,
+        to: 2,
+        num_containers: 1,
+    };
+    assert!(Day5::part1(&(crates.clone(), vec![movement])).is_err());
+    let movement = Movement {
+        from: 1,
+        to: 1,
+        num_containers: 2,
+    };
+    assert!(Day5::part2(&(crates, vec![movement])).is_err());
+}
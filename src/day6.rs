@@ -1,40 +1,65 @@
-use std::{collections::HashSet, hash::Hash, str::FromStr};
+use std::collections::HashSet;
 
-use crate::helper::{read_lines, ParseError};
+use anyhow::Result;
 
-#[test]
-pub fn test_day6_pt_1() {
-    let lines: Vec<String> = read_lines(6, false);
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::ParseError,
+    solution::{solution_api, Answer, Solution},
+};
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        match input.lines().next() {
+            Some(line) if !line.is_empty() => Ok(line.to_owned()),
+            _ => Err(ParseError::new("expected a datastream", "")
+                .at_line(1)
+                .for_day(6)
+                .into()),
+        }
+    }
+
+    fn part1(line: &Self::Input) -> Result<Answer> {
+        Ok(find_window(line, 4).into())
+    }
+
+    fn part2(line: &Self::Input) -> Result<Answer> {
+        Ok(find_window(line, 14).into())
+    }
+}
 
-    let line = lines[0].to_owned();
+solution_api!(Day6);
 
-    let signals = find_window(&line, 4);
-    println!("Part1 score is {:?}", signals);
-    assert!(false);
+#[test]
+pub fn test_day6_pt_1() {
+    let line = Day6::parse(&read_string(6, false).unwrap()).unwrap();
+    assert_eq!(Day6::part1(&line).unwrap(), Answer::from(1876));
 }
 #[test]
 pub fn test_day6_pt_2() {
-    let lines: Vec<String> = read_lines(6, false);
-
-    let line = lines[0].to_owned();
-
-    let signals = find_window(&line, 14);
-    println!("Part2 score is {:?}", signals);
-    assert!(false);
+    let line = Day6::parse(&read_string(6, false).unwrap()).unwrap();
+    assert_eq!(Day6::part2(&line).unwrap(), Answer::from(2202));
 }
 
-fn find_window(line: &str, window_size: usize) -> usize {
+/// Characters read up to the end of the first `window_size` long run of
+/// distinct characters.
+pub fn find_window(line: &str, window_size: usize) -> usize {
     for (i, w) in line
         .chars()
         .collect::<Vec<char>>()
         .windows(window_size)
         .enumerate()
     {
-        let unique: HashSet<&char> = HashSet::from_iter::<Vec<&char>>(w.into_iter().collect());
+        let unique: HashSet<&char> = HashSet::from_iter(w.iter());
         if unique.len() == window_size {
             return i + window_size;
         }
     }
 
-    return 0;
+    0
 }
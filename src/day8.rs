@@ -1,200 +1,127 @@
-use std::{collections::HashSet, hash::Hash, str::FromStr};
+use anyhow::Result;
 
-use crate::helper::{read_lines, ParseError};
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    grid::{Grid, Pos},
+    point::Direction,
+    solution::{solution_api, Answer, Params, Part, Solution},
+};
 
-type Grid = Vec<Vec<u32>>;
+pub struct Day8;
 
-#[test]
-pub fn test_day8_pt_1() {
-    let lines: Vec<String> = read_lines(8, false);
+impl Solution for Day8 {
+    type Input = Grid<u32>;
 
-    let grid: Grid = lines
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|char| char.to_digit(10).unwrap())
-                .collect()
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(Grid::parse(input, "expected a tree height 0-9", |char| {
+            char.to_digit(10)
         })
-        .collect();
-
-    let rows = grid.len();
-    let width = grid[0].len();
-    let mut visible: Vec<u32> = Vec::new();
-    for row in 1..rows - 1 {
-        for col in 1..width - 1 {
-            if is_visible(row, col, &grid) {
-                visible.push(grid[row][col]);
-            }
-        }
+        .map_err(|err| err.for_day(8))?)
     }
 
-    let outside_grid = grid.len() * 2 + (grid[0].len() - 2) * 2;
-    println!("Part1 score is {:?}", visible.len() + outside_grid);
-    assert!(false);
-}
-
-fn is_visible(row: usize, col: usize, grid: &Grid) -> bool {
-    let tree = grid[row][col];
-    let mut step = 1;
-    let height = grid.len();
-    let width = grid[0].len();
-
-    let mut left_visible = true;
-    let mut right_visible = true;
-    let mut top_visible = true;
-    let mut bottom_visible = true;
-
-    while (left_visible || right_visible || top_visible || bottom_visible)
-        && (is_inside_grid(row, col, width, height, step))
-    {
-        if left_visible {
-            if let Some(left_tree) = left_scan(row, col, step, grid) {
-                left_visible = left_tree < tree;
-            }
-        }
-        if right_visible {
-            if let Some(right_tree) = right_scan(row, col, step, grid) {
-                right_visible = right_tree < tree;
-            }
-        }
-        if top_visible {
-            if let Some(top_tree) = top_scan(row, col, step, grid) {
-                top_visible = top_tree < tree;
-            }
-        }
-        if bottom_visible {
-            if let Some(bottom_tree) = bottom_scan(row, col, step, grid) {
-                bottom_visible = bottom_tree < tree;
-            }
-        }
-
-        step = step + 1;
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        Ok(grid
+            .positions()
+            .filter(|&pos| is_visible(pos, grid))
+            .count()
+            .into())
     }
 
-    return left_visible || right_visible || top_visible || bottom_visible;
-}
-
-fn left_scan(row: usize, col: usize, step: usize, grid: &Grid) -> Option<u32> {
-    if (col as i8 - step as i8) >= 0 {
-        Some(grid[row][col - step])
-    } else {
-        None
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        Ok(grid
+            .positions()
+            .map(|pos| scenic_score(pos, grid))
+            .max()
+            .unwrap_or(0)
+            .into())
     }
 }
-fn right_scan(row: usize, col: usize, step: usize, grid: &Grid) -> Option<u32> {
-    let width = grid[0].len();
 
-    if col + step <= width - 1 {
-        Some(grid[row][col + step])
-    } else {
-        None
-    }
-}
-fn top_scan(row: usize, col: usize, step: usize, grid: &Grid) -> Option<u32> {
-    if (row as i8 - step as i8) >= 0 {
-        Some(grid[row - step][col])
-    } else {
-        None
-    }
-}
+solution_api!(Day8);
 
-fn bottom_scan(row: usize, col: usize, step: usize, grid: &Grid) -> Option<u32> {
-    let width = grid[0].len();
-
-    if row + step <= grid.len() - 1 {
-        Some(grid[row + step][col])
-    } else {
-        None
-    }
+#[test]
+pub fn test_day8_pt_1() {
+    let grid = Day8::parse(&read_string(8, false).unwrap()).unwrap();
+    assert_eq!(Day8::part1(&grid).unwrap(), Answer::from(1782));
 }
 
-fn is_inside_grid(row: usize, col: usize, width: usize, height: usize, step: usize) -> bool {
-    if (col as i8 - step as i8) >= 0 {
-        return true;
-    } else if (col + step) <= width - 1 {
-        return true;
-    }
-    if (row as i8 - step as i8) >= 0 {
-        return true;
-    } else if (row + step) <= height - 1 {
-        return true;
-    } else {
-        return false;
-    }
+/// Visible from outside when every tree in some direction is shorter.
+fn is_visible(pos: Pos, grid: &Grid<u32>) -> bool {
+    let tree = grid[pos];
+    Direction::ALL.into_iter().any(|direction| {
+        grid.ray(pos, direction.offset())
+            .all(|(_, &other_tree)| other_tree < tree)
+    })
 }
 
 #[test]
 pub fn test_day8_pt_2() {
-    let lines: Vec<String> = read_lines(8, false);
-
-    let grid: Grid = lines
-        .iter()
-        .map(|line| {
-            line.chars()
-                .map(|char| char.to_digit(10).unwrap())
-                .collect()
-        })
-        .collect();
+    let grid = Day8::parse(&read_string(8, false).unwrap()).unwrap();
+    assert_eq!(Day8::part2(&grid).unwrap(), Answer::from(474606));
+}
 
-    let rows = grid.len();
-    let width = grid[0].len();
-    let mut max_score: u32 = 0;
-    for row in 0..rows {
-        for col in 0..width {
-            let score = scenic_score(row, col, &grid);
-            if score > max_score {
-                max_score = score;
+fn scenic_score(pos: Pos, grid: &Grid<u32>) -> u32 {
+    let tree = grid[pos];
+    Direction::ALL
+        .into_iter()
+        .map(|direction| {
+            let mut distance = 0;
+            for (_, &other_tree) in grid.ray(pos, direction.offset()) {
+                distance += 1;
+                if other_tree >= tree {
+                    break;
+                }
             }
-        }
-    }
-
-    println!("Part2 score is {:?}", max_score);
-    assert!(false);
+            distance
+        })
+        .product()
 }
 
-fn scenic_score(row: usize, col: usize, grid: &Grid) -> u32 {
-    let tree = grid[row][col];
-    let mut step = 1;
-    let height = grid.len();
-    let width = grid[0].len();
-
-    let mut left_distance = 0;
-    let mut right_distance = 0;
-    let mut top_distance = 0;
-    let mut bottom_distance = 0;
-    let mut left_visible = true;
-    let mut right_visible = true;
-    let mut top_visible = true;
-    let mut bottom_visible = true;
-
-    while (is_inside_grid(row, col, width, height, step)) {
-        if left_visible {
-            if let Some(left_tree) = left_scan(row, col, step, grid) {
-                left_visible = left_tree < tree;
-                left_distance = left_distance + 1;
-            }
-        }
-        if right_visible {
-            if let Some(right_tree) = right_scan(row, col, step, grid) {
-                right_visible = right_tree < tree;
-                right_distance = right_distance + 1;
-            }
-        }
-        if top_visible {
-            if let Some(top_tree) = top_scan(row, col, step, grid) {
-                top_visible = top_tree < tree;
-                top_distance = top_distance + 1;
+/// Walks the rows and columns by index, for differential testing against the
+/// grid rays above.
+#[cfg_attr(not(feature = "cli"), allow(dead_code))]
+pub(crate) fn brute_force(input: &str, params: &Params, part: Part) -> Result<Answer> {
+    let rows: Vec<Vec<u32>> = input
+        .lines()
+        .map(|line| line.chars().filter_map(|c| c.to_digit(10)).collect())
+        .collect();
+    Day8::parse_with(input, params)?;
+    let (height, width) = (rows.len(), rows[0].len());
+
+    let mut visible = 0;
+    let mut best = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let tree = rows[y][x];
+            let lines: [Vec<u32>; 4] = [
+                (0..y).rev().map(|y| rows[y][x]).collect(),
+                (x + 1..width).map(|x| rows[y][x]).collect(),
+                (y + 1..height).map(|y| rows[y][x]).collect(),
+                (0..x).rev().map(|x| rows[y][x]).collect(),
+            ];
+            if lines
+                .iter()
+                .any(|line| line.iter().all(|&other| other < tree))
+            {
+                visible += 1;
             }
-        }
-        if bottom_visible {
-            if let Some(bottom_tree) = bottom_scan(row, col, step, grid) {
-                bottom_visible = bottom_tree < tree;
-                bottom_distance = bottom_distance + 1;
+            let mut score = 1;
+            for line in &lines {
+                let mut distance = 0;
+                for &other in line {
+                    distance += 1;
+                    if other >= tree {
+                        break;
+                    }
+                }
+                score *= distance;
             }
+            best = best.max(score);
         }
-
-        step = step + 1;
     }
-
-    return left_distance * right_distance * top_distance * bottom_distance;
+    Ok(match part {
+        Part::One => visible.into(),
+        Part::Two => best.into(),
+    })
 }
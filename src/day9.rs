@@ -1,8 +1,16 @@
-use anyhow::{anyhow, Context, Result};
-use regex::internal::Inst;
-use std::{collections::HashSet, hash::Hash, str::FromStr};
-
-use crate::helper::{read_lines, ParseError};
+use anyhow::Result;
+use std::{collections::HashSet, str::FromStr};
+
+#[cfg(test)]
+use crate::helper::read_string;
+use crate::{
+    error::{parse_lines, ParseError},
+    grid::{Grid, Pos},
+    point::{Direction, Point},
+    solution::{solution_api, Answer, Part, Solution},
+    trace,
+    visual::{record, Frame, Recorder},
+};
 
 #[derive(Debug, Clone)]
 enum Command {
@@ -12,20 +20,14 @@ enum Command {
     Left { steps: usize },
 }
 
-#[derive(Debug, Clone)]
-enum Instruction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
 impl FromStr for Command {
-    type Err = anyhow::Error;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Some((cmd, step)) = s.split_once(" ") {
-            let steps = step.parse::<u8>()?;
+        if let Some((cmd, step)) = s.split_once(' ') {
+            let steps = step.parse::<u8>().map_err(|_| {
+                ParseError::new("expected a step count below 256", step).at_column(cmd.len() + 2)
+            })?;
             match cmd {
                 "U" => Ok(Command::Up {
                     steps: steps.into(),
@@ -39,113 +41,128 @@ impl FromStr for Command {
                 "L" => Ok(Command::Left {
                     steps: steps.into(),
                 }),
-                _ => Err(anyhow!("Missing attribute: {}", cmd)),
+                _ => Err(ParseError::new("expected one of U, R, D or L", cmd).at_column(1)),
             }
         } else {
-            Err(anyhow!("Not a command"))
+            Err(ParseError::new("expected a direction and a step count", s))
         }
     }
 }
 
-#[test]
-pub fn test_day9_pt_1() {
-    let lines: Vec<String> = read_lines(9, false);
-    let commands = lines.iter().map(|l| l.parse::<Command>().unwrap());
-    let instructions: Vec<Instruction> = commands.flat_map(to_instruction).collect();
+pub struct Day9;
 
-    let mut head = (0, 0);
-    let mut tail = (0, 0);
-    let mut visited = HashSet::<(i16, i16)>::new();
+impl Solution for Day9 {
+    type Input = Vec<Direction>;
 
-    for instruction in &instructions {
-        head = move_head(head, instruction);
-        tail = move_tail(&tail, head);
+    fn parse(input: &str) -> Result<Self::Input> {
+        let commands = parse_lines(9, input, |l| l.parse::<Command>())?;
+        Ok(commands.into_iter().flat_map(to_instruction).collect())
+    }
 
-        // println!("head: {:?} tail: {:?}", head, tail);
-        visited.insert(tail);
+    fn part1(instructions: &Self::Input) -> Result<Answer> {
+        Ok(pull_rope(instructions, 2, &mut ()).into())
     }
-    println!("Part1 score is {:?}", visited.len());
-    assert!(false);
-}
 
-fn move_head(position: (i16, i16), instruction: &Instruction) -> (i16, i16) {
-    let (x, y) = position;
-    match instruction {
-        Instruction::Up => (x, y + 1),
-        Instruction::Right => (x + 1, y),
-        Instruction::Down => (x, y - 1),
-        Instruction::Left => (x - 1, y),
+    fn part2(instructions: &Self::Input) -> Result<Answer> {
+        Ok(pull_rope(instructions, 10, &mut ()).into())
     }
 }
 
-fn move_tail(tail: &(i16, i16), head: (i16, i16)) -> (i16, i16) {
-    let (tail_x, tail_y) = tail.clone();
-    let (head_x, head_y) = head;
+solution_api!(Day9);
 
-    if tail_x == head_x && tail_y.abs_diff(head_y) > 1 {
-        if tail_y > head_y {
-            (tail_x, tail_y - 1)
-        } else {
-            (tail_x, tail_y + 1)
-        }
-    } else if tail_y == head_y && tail_x.abs_diff(head_x) > 1 {
-        if tail_x > head_x {
-            (tail_x - 1, tail_y)
-        } else {
-            (tail_x + 1, tail_y)
+/// Frames of the rope after every step, for `aoc record`.
+pub fn simulate(input: &str, part: Part, recorder: &mut dyn Recorder) -> Result<()> {
+    let knots = match part {
+        Part::One => 2,
+        Part::Two => 10,
+    };
+    pull_rope(&Day9::parse(input)?, knots, recorder);
+    Ok(())
+}
+
+/// Moves the head of a rope of `knots` knots and counts the positions the
+/// last knot visits.
+fn pull_rope(instructions: &[Direction], knots: usize, recorder: &mut dyn Recorder) -> usize {
+    let mut rope = vec![Point::ORIGIN; knots];
+    let mut visited = HashSet::from([Point::ORIGIN]);
+
+    for instruction in instructions {
+        rope[0] += instruction.offset();
+        for i in 1..rope.len() {
+            rope[i] = move_tail(rope[i], rope[i - 1]);
         }
-    } else if head_x > tail_x && head_y > tail_y && not_close_diagonal(head, tail) {
-        // Diagonally top-right
-        (tail_x + 1, tail_y + 1)
-    } else if head_x > tail_x && head_y < tail_y && not_close_diagonal(head, tail) {
-        // Diagonally bottom-right
-        (tail_x + 1, tail_y - 1)
-    } else if head_x < tail_x && head_y > tail_y && not_close_diagonal(head, tail) {
-        // Diagonally top-left
-        (tail_x - 1, tail_y + 1)
-    } else if head_x < tail_x && head_y < tail_y && not_close_diagonal(head, tail) {
-        // Diagonally bottom-left
-        (tail_x - 1, tail_y - 1)
-    } else {
-        tail.clone()
+        trace::event!(Trace, "step", head = rope[0], tail = rope[knots - 1]);
+        visited.insert(rope[knots - 1]);
+        record(recorder, || rope_frame(&rope, &visited));
     }
+    visited.len()
 }
-fn not_close_diagonal(head: (i16, i16), tail: &(i16, i16)) -> bool {
-    head.1.abs_diff(tail.1) + head.0.abs_diff(tail.0) > 2
+
+/// The visited positions as `#`, the start as `s`, the head as `H` and the
+/// other knots numbered from 1, focused on the head.
+fn rope_frame(rope: &[Point], visited: &HashSet<Point>) -> (Frame, Pos) {
+    let points = || rope.iter().chain(visited);
+    let lower = Point::new(
+        points().map(|point| point.x).min().unwrap_or(0),
+        points().map(|point| point.y).min().unwrap_or(0),
+    );
+    let upper = Point::new(
+        points().map(|point| point.x).max().unwrap_or(0),
+        points().map(|point| point.y).max().unwrap_or(0),
+    );
+    let size = upper - lower + Point::new(1, 1);
+    let mut frame = Grid::new(size.x as usize, size.y as usize, '.');
+    let mut draw = |point: Point, cell: char| {
+        frame[Pos::try_from(point - lower).unwrap()] = cell;
+    };
+
+    for &point in visited {
+        draw(point, '#');
+    }
+    draw(Point::ORIGIN, 's');
+    for (i, &knot) in rope.iter().enumerate().rev() {
+        draw(knot, char::from_digit(i as u32 % 10, 10).unwrap());
+    }
+    draw(rope[0], 'H');
+    (frame, Pos::try_from(rope[0] - lower).unwrap())
+}
+
+#[test]
+pub fn test_day9_pt_1() {
+    let instructions = Day9::parse(&read_string(9, false).unwrap()).unwrap();
+    assert_eq!(Day9::part1(&instructions).unwrap(), Answer::from(6243));
+}
+
+/// A knot more than one step away from the knot ahead moves one step
+/// towards it, diagonally if they are not in line.
+fn move_tail(tail: Point, head: Point) -> Point {
+    if tail.chebyshev(head) > 1 {
+        tail + (head - tail).signum()
+    } else {
+        tail
+    }
 }
-fn to_instruction(cmd: Command) -> Vec<Instruction> {
+
+fn to_instruction(cmd: Command) -> Vec<Direction> {
     match cmd {
-        Command::Up { steps } => vec![Instruction::Up; steps],
-        Command::Right { steps } => vec![Instruction::Right; steps],
-        Command::Down { steps } => vec![Instruction::Down; steps],
-        Command::Left { steps } => vec![Instruction::Left; steps],
+        Command::Up { steps } => vec![Direction::Up; steps],
+        Command::Right { steps } => vec![Direction::Right; steps],
+        Command::Down { steps } => vec![Direction::Down; steps],
+        Command::Left { steps } => vec![Direction::Left; steps],
     }
 }
 
 #[test]
-pub fn test_day9_pt_2() {
-    let lines: Vec<String> = read_lines(9, false);
-    let commands = lines.iter().map(|l| l.parse::<Command>().unwrap());
-    let instructions: Vec<Instruction> = commands.flat_map(to_instruction).collect();
-
-    let mut head = (0, 0);
-    let mut tails = [(0, 0); 9];
-    let mut visited = HashSet::<(i16, i16)>::new();
-
-    for instruction in &instructions {
-        head = move_head(head, instruction);
-        let mut next_knot = head;
-        for mut tail in tails.iter_mut() {
-            let new_tail = move_tail(tail, next_knot);
-            tail.0 = new_tail.0;
-            tail.1 = new_tail.1;
-
-            next_knot = new_tail;
-        }
+pub fn test_move_tail() {
+    let tail = Point::new(1, 1);
+    assert_eq!(move_tail(tail, Point::new(2, 2)), tail);
+    assert_eq!(move_tail(tail, Point::new(3, 1)), Point::new(2, 1));
+    assert_eq!(move_tail(tail, Point::new(2, 3)), Point::new(2, 2));
+    assert_eq!(move_tail(tail, Point::new(3, 3)), Point::new(2, 2));
+}
 
-        // println!("head: {:?} tail: {:?}", head, tail);
-        visited.insert(tails[8]);
-    }
-    println!("Part2 score is {:?}", visited.len());
-    assert!(false);
+#[test]
+pub fn test_day9_pt_2() {
+    let instructions = Day9::parse(&read_string(9, false).unwrap()).unwrap();
+    assert_eq!(Day9::part2(&instructions).unwrap(), Answer::from(2630));
 }
@@ -0,0 +1,232 @@
+//! Differential testing: runs a slow but obviously correct solver and the real
+//! one on many small generated inputs, and shrinks the first input they
+//! disagree on.
+
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::{
+    day12, day15, day4, day8, find_day,
+    generate::{find_generator, Rng},
+    helper::panic_message,
+    solution::{Answer, Params, Part},
+};
+
+/// A brute-force solver to check a day against, on generated inputs of at
+/// most `max_size`.
+pub struct Reference {
+    pub day: u8,
+    pub solve: fn(&str, &Params, Part) -> Result<Answer>,
+    pub max_size: usize,
+}
+
+pub static REFERENCES: &[Reference] = &[
+    Reference {
+        day: 4,
+        solve: day4::brute_force,
+        max_size: 30,
+    },
+    Reference {
+        day: 8,
+        solve: day8::brute_force,
+        max_size: 12,
+    },
+    Reference {
+        day: 12,
+        solve: day12::brute_force,
+        max_size: 12,
+    },
+    Reference {
+        day: 15,
+        solve: day15::brute_force,
+        max_size: 20,
+    },
+];
+
+pub fn find_reference(day: u8) -> Option<&'static Reference> {
+    REFERENCES.iter().find(|reference| reference.day == day)
+}
+
+/// What the optimized solver made of an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answer(Answer),
+    Error(String),
+    Panic(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Answer(answer) => write!(f, "{}", answer),
+            Outcome::Error(err) => write!(f, "error: {}", err),
+            Outcome::Panic(message) => write!(f, "panic: {}", message),
+        }
+    }
+}
+
+/// The first generated input the two solvers disagree on.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: Part,
+    pub seed: u64,
+    pub size: usize,
+    /// What the generator parses inputs of `size` with.
+    pub params: Params,
+    pub input: String,
+    /// The fewest lines of `input` the solvers still disagree on.
+    pub minimized: String,
+    /// What the brute-force solver answered for `minimized`.
+    pub expected: Answer,
+    pub actual: Outcome,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {} part {} disagrees on seed {} size {}",
+            self.day, self.part, self.seed, self.size
+        )?;
+        if !self.params.is_empty() {
+            writeln!(f, "params {}", self.params)?;
+        }
+        writeln!(f, "expected {}, got {}", self.expected, self.actual)?;
+        writeln!(f, "minimized input:")?;
+        write!(f, "{}", self.minimized)?;
+        if !self.minimized.ends_with('\n') {
+            writeln!(f)?;
+        }
+        writeln!(f, "generated input:")?;
+        write!(f, "{}", self.input)
+    }
+}
+
+/// Runs the optimized solver of `day`, keeping its panics.
+pub fn run_optimized(day: u8, input: &str, params: &Params, part: Part) -> Outcome {
+    let Some(day) = find_day(day) else {
+        return Outcome::Error(format!("Day {} is not solved", day));
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| {
+        day.solver.run_with(input, params, part)
+    })) {
+        Ok(Ok(answer)) => Outcome::Answer(answer),
+        Ok(Err(err)) => Outcome::Error(format!("{:#}", err)),
+        Err(payload) => Outcome::Panic(panic_message(payload.as_ref())),
+    }
+}
+
+/// The brute-force answer and the optimized outcome when they differ. An
+/// input the brute-force solver rejects proves nothing, so it never differs.
+fn disagreement(
+    reference: &Reference,
+    input: &str,
+    params: &Params,
+    part: Part,
+) -> Option<(Answer, Outcome)> {
+    let expected = (reference.solve)(input, params, part).ok()?;
+    let actual = run_optimized(reference.day, input, params, part);
+    (actual != Outcome::Answer(expected.clone())).then_some((expected, actual))
+}
+
+/// Checks both parts on `seeds` generated inputs, with sizes cycling from 1
+/// to the reference's `max_size`.
+pub fn find_mismatch(reference: &Reference, seeds: u64) -> Result<Option<Mismatch>> {
+    let generator = find_generator(reference.day)
+        .ok_or_else(|| anyhow!("There is no generator for day {}", reference.day))?;
+    for seed in 0..seeds {
+        let size = 1 + seed as usize % reference.max_size;
+        let input = (generator.generate)(&mut Rng::new(seed), size);
+        let params = (generator.params)(size);
+        for part in Part::ALL {
+            if disagreement(reference, &input, &params, part).is_none() {
+                continue;
+            }
+            let minimized = minimize(&input, |lines| {
+                disagreement(reference, lines, &params, part).is_some()
+            });
+            let (expected, actual) = disagreement(reference, &minimized, &params, part)
+                .expect("the minimized input should still disagree");
+            return Ok(Some(Mismatch {
+                day: reference.day,
+                part,
+                seed,
+                size,
+                params,
+                input,
+                minimized,
+                expected,
+                actual,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// Removes chunks of lines from `input`, halving the chunk size down to
+/// single lines, as long as `fails` still holds for what is left.
+pub fn minimize(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let join =
+        |lines: &[&str]| -> String { lines.iter().map(|line| format!("{}\n", line)).collect() };
+    let mut lines: Vec<&str> = input.lines().collect();
+    let mut chunk = lines.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<&str> = [&lines[..start], &lines[end..]].concat();
+            if !candidate.is_empty() && fails(&join(&candidate)) {
+                lines = candidate;
+            } else {
+                start = end;
+            }
+        }
+        chunk /= 2;
+    }
+    join(&lines)
+}
+
+#[test]
+pub fn test_minimize() {
+    let input = "a\nb\nc\nd\ne\nf\ng\n";
+    let fails = |text: &str| text.contains("b\n") && text.contains("f\n");
+    assert_eq!(minimize(input, fails), "b\nf\n");
+    assert_eq!(minimize(input, |_| true), "g\n");
+}
+
+#[test]
+pub fn test_references_agree() {
+    for reference in REFERENCES {
+        if let Some(mismatch) = find_mismatch(reference, 60).unwrap() {
+            panic!("{}", mismatch);
+        }
+    }
+}
+
+#[test]
+pub fn test_mismatch_is_minimized() {
+    // Part 2 of day 4 counts overlaps, pretend the optimized solver answers
+    // part 1 for it.
+    fn broken(input: &str, params: &Params, part: Part) -> Result<Answer> {
+        day4::brute_force(
+            input,
+            params,
+            if part == Part::One { Part::Two } else { part },
+        )
+    }
+    let reference = Reference {
+        day: 4,
+        solve: broken,
+        max_size: 10,
+    };
+    let mismatch = find_mismatch(&reference, 20).unwrap().unwrap();
+    assert_eq!(mismatch.part, Part::One);
+    assert_eq!(mismatch.minimized.lines().count(), 1);
+    assert_eq!(mismatch.expected, Answer::from(1));
+    assert_eq!(mismatch.actual, Outcome::Answer(Answer::from(0)));
+}
@@ -0,0 +1,146 @@
+use std::{error::Error, fmt};
+
+/// Where and why a puzzle input could not be parsed.
+///
+/// Line and column are 1-based. Parsers that only see a single line leave the
+/// line unset and the caller fills it in with [`ParseError::at_line`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u8>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(message: &str, text: &str) -> ParseError {
+        ParseError {
+            day: None,
+            line: None,
+            column: None,
+            text: text.to_owned(),
+            message: message.to_owned(),
+        }
+    }
+
+    /// Points at `rest`, which must be a suffix of `input`.
+    pub fn at_offset(message: &str, input: &str, rest: &str) -> ParseError {
+        let offset = input.len().saturating_sub(rest.len());
+        let consumed = &input[..offset];
+        let line = consumed.matches('\n').count() + 1;
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+        let column = consumed[line_start..].chars().count() + 1;
+        let text = input[line_start..].lines().next().unwrap_or("");
+
+        ParseError::new(message, text)
+            .at_line(line)
+            .at_column(column)
+    }
+
+    pub fn for_day(mut self, day: u8) -> ParseError {
+        self.day.get_or_insert(day);
+        self
+    }
+
+    /// Sets the line unless it is already known, and shifts a line relative
+    /// error onto `line`.
+    pub fn at_line(mut self, line: usize) -> ParseError {
+        self.line = Some(self.line.map_or(line, |inner| line + inner - 1));
+        self
+    }
+
+    pub fn at_column(mut self, column: usize) -> ParseError {
+        self.column.get_or_insert(column);
+        self
+    }
+
+    /// Moves the column right by `columns`, for errors from parsing a slice
+    /// that starts part way into the line.
+    pub fn shifted(mut self, columns: usize) -> ParseError {
+        if let Some(column) = self.column.as_mut() {
+            *column += columns;
+        }
+        self
+    }
+}
+
+/// `day 5, line 2, column 6: message: "text"`, leaving out what is unknown.
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut location = Vec::new();
+        if let Some(day) = self.day {
+            location.push(format!("day {}", day));
+        }
+        if let Some(line) = self.line {
+            location.push(format!("line {}", line));
+        }
+        if let Some(column) = self.column {
+            location.push(format!("column {}", column));
+        }
+        if !location.is_empty() {
+            write!(f, "{}: ", location.join(", "))?;
+        }
+        write!(f, "{}", self.message)?;
+        if !self.text.is_empty() {
+            write!(f, ": {:?}", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Converts a nom failure on `input` into a [`ParseError`] pointing at the
+/// position where the parser gave up.
+pub fn from_nom(input: &str, err: nom::Err<nom::error::Error<&str>>) -> ParseError {
+    match err {
+        nom::Err::Incomplete(_) => ParseError::at_offset("unexpected end of input", input, ""),
+        nom::Err::Error(err) | nom::Err::Failure(err) => ParseError::at_offset(
+            &format!("expected {}", err.code.description()),
+            input,
+            err.input,
+        ),
+    }
+}
+
+/// Parses every line of `input` with `parse_line`, numbering errors by line.
+pub fn parse_lines<T>(
+    day: u8,
+    input: &str,
+    parse_line: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_line(line).map_err(|err| err.at_line(i + 1).for_day(day)))
+        .collect()
+}
+
+#[test]
+pub fn test_at_offset() {
+    let input = "move 1 from 2 to 3\nmove x from 1 to 2\n";
+    let err = ParseError::at_offset("expected a number", input, &input[24..]).for_day(5);
+    assert_eq!(err.line, Some(2));
+    assert_eq!(err.column, Some(6));
+    assert_eq!(err.text, "move x from 1 to 2");
+    assert_eq!(
+        err.to_string(),
+        "day 5, line 2, column 6: expected a number: \"move x from 1 to 2\""
+    );
+    // Errors about the input as a whole have no text to show.
+    let err = ParseError::new("expected a grid", "").for_day(8);
+    assert_eq!(err.to_string(), "day 8: expected a grid");
+}
+
+#[test]
+pub fn test_parse_lines() {
+    let err = parse_lines(1, "1\n2\nthree\n", |line| {
+        line.parse::<u32>()
+            .map_err(|_| ParseError::new("not a number", line).at_column(1))
+    })
+    .unwrap_err();
+    assert_eq!(err.day, Some(1));
+    assert_eq!(err.line, Some(3));
+    assert_eq!(err.text, "three");
+}
@@ -0,0 +1,266 @@
+//! Downloads puzzle inputs and examples from adventofcode.com into the input
+//! directory. A file that is already there is never downloaded again.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::helper::{input_dir, input_name, normalize};
+
+/// The session cookie of a logged in adventofcode.com account.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub(crate) const YEAR: u16 = 2022;
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), " input fetcher");
+
+/// Where a fetched input ended up, and whether it had to be downloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fetched {
+    pub path: PathBuf,
+    pub downloaded: bool,
+}
+
+pub struct Client {
+    base_url: String,
+    session: String,
+    cache_dir: PathBuf,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    /// Fetches from adventofcode.com into [`input_dir`].
+    pub fn new(session: &str) -> Client {
+        Client {
+            base_url: BASE_URL.to_string(),
+            session: session.trim().to_string(),
+            cache_dir: input_dir(),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        }
+    }
+
+    /// A client with the session from `AOC_SESSION`.
+    pub fn from_env() -> Result<Client> {
+        let session = env::var(SESSION_VAR).with_context(|| {
+            format!(
+                "Set {} to your adventofcode.com session cookie",
+                SESSION_VAR
+            )
+        })?;
+        Ok(Client::new(&session))
+    }
+
+    pub fn base_url(mut self, url: &str) -> Client {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn cache_dir(mut self, dir: &Path) -> Client {
+        self.cache_dir = dir.to_path_buf();
+        self
+    }
+
+    /// The input of `day`, downloading it first unless it is cached as
+    /// `dayN.txt`.
+    pub fn fetch_input(&self, day: u8) -> Result<Fetched> {
+        self.cached(day, false, || {
+            Ok(normalize(
+                &self.get(&format!("/{}/day/{}/input", YEAR, day))?,
+            ))
+        })
+    }
+
+    /// The example of `day`, the `block`th `<pre><code>` block of the puzzle
+    /// page counting from 1, downloading it first unless it is cached as
+    /// `dayN_test.txt`. Which block is the example differs per day.
+    pub fn fetch_example(&self, day: u8, block: usize) -> Result<Fetched> {
+        self.cached(day, true, || {
+            let page = self.get(&format!("/{}/day/{}", YEAR, day))?;
+            let blocks = code_blocks(&page);
+            let example = block
+                .checked_sub(1)
+                .and_then(|i| blocks.get(i))
+                .ok_or_else(|| {
+                    anyhow!(
+                        "The day {} puzzle page has {} code blocks, not a block {}",
+                        day,
+                        blocks.len(),
+                        block
+                    )
+                })?;
+            Ok(normalize(example))
+        })
+    }
+
+    fn cached(
+        &self,
+        day: u8,
+        test: bool,
+        download: impl FnOnce() -> Result<String>,
+    ) -> Result<Fetched> {
+        let path = self
+            .cache_dir
+            .join(format!("{}.txt", input_name(day, test)));
+        if path.exists() {
+            return Ok(Fetched {
+                path,
+                downloaded: false,
+            });
+        }
+        write_atomic(&path, &download()?)?;
+        Ok(Fetched {
+            path,
+            downloaded: true,
+        })
+    }
+
+    fn get(&self, path: &str) -> Result<String> {
+        let url = format!("{}{}", self.base_url, path);
+        read_response(&url, self.request("GET", &url).call())
+    }
+
+    /// Posts `form` to `path` on the site, like the answer form does.
+    pub(crate) fn post(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        let url = format!("{}{}", self.base_url, path);
+        read_response(&url, self.request("POST", &url).send_form(form))
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        self.agent
+            .request(method, url)
+            .set("Cookie", &format!("session={}", self.session))
+    }
+}
+
+fn read_response(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String> {
+    let response = response.map_err(|err| match err {
+        ureq::Error::Status(400, _) => {
+            anyhow!("{} rejected the session cookie, is it expired?", url)
+        }
+        ureq::Error::Status(404, _) => anyhow!("{} is not unlocked yet", url),
+        ureq::Error::Status(status, _) => anyhow!("{} answered {}", url, status),
+        ureq::Error::Transport(err) => anyhow!("Could not reach {}: {}", url, err),
+    })?;
+    response
+        .into_string()
+        .with_context(|| format!("Could not read the response from {}", url))
+}
+
+/// Writes next to `path` first so an interrupted download never leaves a
+/// partial file that would be taken for a cached one.
+fn write_atomic(path: &Path, text: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Could not create {}", dir.display()))?;
+    }
+    let partial = path.with_extension("part");
+    fs::write(&partial, text)
+        .and_then(|_| fs::rename(&partial, path))
+        .with_context(|| format!("Could not write {}", path.display()))
+}
+
+/// Every `<pre><code>` block of a puzzle page, with tags stripped and
+/// entities decoded. The example is usually the first, but not always: day 9
+/// shows rope diagrams and day 10 a shorter program before it.
+pub fn code_blocks(page: &str) -> Vec<String> {
+    const OPEN: &str = "<pre><code>";
+    let mut blocks = Vec::new();
+    let mut rest = page;
+    while let Some(start) = rest.find(OPEN) {
+        rest = &rest[start + OPEN.len()..];
+        let Some(end) = rest.find("</code></pre>") else {
+            break;
+        };
+        blocks.push(text_of(&rest[..end]));
+        rest = &rest[end..];
+    }
+    blocks
+}
+
+/// The text of an HTML fragment, with tags stripped and entities decoded.
+pub(crate) fn text_of(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[test]
+pub fn test_code_blocks() {
+    let page =
+        "<p>x</p><pre><code>a &lt;<em>b</em>&gt; &amp;lt;\n</code></pre><pre><code>c</code></pre>";
+    assert_eq!(code_blocks(page), vec!["a <b> &lt;\n", "c"]);
+    assert!(code_blocks("<p>nothing</p>").is_empty());
+    assert!(code_blocks("<pre><code>cut off").is_empty());
+}
+
+#[cfg(test)]
+fn puzzle_site(received: &crate::stub::Received) -> (u16, String) {
+    let fixture = |path| fs::read_to_string(path).unwrap();
+    match received.path.as_str() {
+        _ if received.cookie.as_deref() != Some("session=secret") => (400, String::new()),
+        "/2022/day/1" => (200, fixture("resources/fixtures/day1.html")),
+        "/2022/day/1/input" => (200, fixture("resources/day1.txt").replace('\n', "\r\n")),
+        _ => (404, String::new()),
+    }
+}
+
+#[test]
+pub fn test_fetch_caches() {
+    use crate::stub::{temp_dir, Stub};
+
+    let stub = Stub::serve(puzzle_site);
+    let dir = temp_dir("fetch");
+    let client = Client::new("secret\n").base_url(&stub.url).cache_dir(&dir);
+
+    let input = client.fetch_input(1).unwrap();
+    assert!(input.downloaded);
+    assert_eq!(input.path, dir.join("day1.txt"));
+    assert_eq!(
+        fs::read_to_string(&input.path).unwrap(),
+        fs::read_to_string("resources/day1.txt").unwrap()
+    );
+    let example = client.fetch_example(1, 1).unwrap();
+    assert_eq!(example.path, dir.join("day1_test.txt"));
+    assert_eq!(
+        fs::read_to_string(&example.path).unwrap(),
+        fs::read_to_string("resources/day1_test.txt").unwrap()
+    );
+
+    assert!(!client.fetch_input(1).unwrap().downloaded);
+    assert!(!client.fetch_example(1, 1).unwrap().downloaded);
+    let paths: Vec<String> = stub.received().into_iter().map(|call| call.path).collect();
+    assert_eq!(paths, vec!["/2022/day/1/input", "/2022/day/1"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+pub fn test_fetch_errors() {
+    use crate::stub::{temp_dir, Stub};
+
+    let stub = Stub::serve(puzzle_site);
+    let dir = temp_dir("fetch-errors");
+    let client = Client::new("secret").base_url(&stub.url).cache_dir(&dir);
+    let err = client.fetch_input(2).unwrap_err();
+    assert!(err.to_string().contains("not unlocked"), "{}", err);
+
+    let expired = Client::new("stale").base_url(&stub.url).cache_dir(&dir);
+    let err = expired.fetch_input(1).unwrap_err();
+    assert!(err.to_string().contains("session cookie"), "{}", err);
+    let err = client.fetch_example(1, 9).unwrap_err();
+    assert!(err.to_string().contains("not a block 9"), "{}", err);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
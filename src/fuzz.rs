@@ -0,0 +1,269 @@
+//! Feeds random and mutated inputs to every day's parser, which must return
+//! an error for bad input rather than panic. Inputs that panic are saved as
+//! fixtures and parsed again by the tests. Arithmetic overflow only panics
+//! with overflow checks on, as in tests and with `--profile fuzz`.
+
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    generate::{find_generator, Rng},
+    helper::{input_hash, panic_message, read_string},
+    Day,
+};
+
+/// Where crashing inputs are saved, as `dayN_<hash>.txt`.
+pub const CRASH_DIR: &str = "resources/fixtures/fuzz";
+
+/// Pieces that tend to break parsers: separators, signs, numbers that do not
+/// fit and numbers that fit but overflow once added up.
+const TOKENS: &[&str] = &[
+    "\n",
+    "\n\n",
+    " ",
+    ",",
+    "-",
+    ": ",
+    "->",
+    "=",
+    "[",
+    "]",
+    "0",
+    "-1",
+    "255",
+    "256",
+    "65536",
+    "2000000000",
+    "-2000000000",
+    "2147483647",
+    "-2147483648",
+    "4294967296",
+    "99999999999999999999",
+    "x",
+    "é",
+    "\t",
+];
+
+/// An input whose parse panicked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crash {
+    pub day: u8,
+    pub input: String,
+    pub message: String,
+}
+
+/// The panic message when parsing `input` panics.
+pub fn parse_panics(day: &Day, input: &str) -> Option<String> {
+    panic::catch_unwind(AssertUnwindSafe(|| day.solver.parse(input)))
+        .err()
+        .map(|payload| panic_message(payload.as_ref()))
+}
+
+/// Valid inputs to start mutating from: the example and a few generated ones.
+pub fn corpus(day: u8, rng: &mut Rng) -> Vec<String> {
+    let mut corpus: Vec<String> = read_string(day, true).into_iter().collect();
+    if let Some(generator) = find_generator(day) {
+        corpus.extend([1, 3, 10].map(|size| (generator.generate)(rng, size)));
+    }
+    corpus
+}
+
+/// One to four random edits of `input`.
+pub fn mutate(rng: &mut Rng, input: &str) -> String {
+    let mut chars: Vec<char> = input.chars().collect();
+    for _ in 0..=rng.below(4) {
+        let at = rng.below(chars.len() + 1);
+        match rng.below(7) {
+            0 => {
+                let end = (at + 1 + rng.below(8)).min(chars.len());
+                chars.drain(at.min(end)..end);
+            }
+            1 => {
+                let token = *rng.pick(TOKENS);
+                chars.splice(at..at, token.chars());
+            }
+            2 => chars.truncate(at),
+            3 if at < chars.len() => chars[at] = char::from(rng.range(32..=126) as u8),
+            4 => {
+                let number = rng.pick(TOKENS).chars().filter(char::is_ascii_digit);
+                let end = (at..chars.len())
+                    .find(|&i| !chars[i].is_ascii_digit())
+                    .unwrap_or(chars.len());
+                chars.splice(at..end, number);
+            }
+            _ => {
+                let mut lines: Vec<String> = chars
+                    .iter()
+                    .collect::<String>()
+                    .lines()
+                    .map(String::from)
+                    .collect();
+                if !lines.is_empty() {
+                    let (i, j) = (rng.below(lines.len()), rng.below(lines.len()));
+                    if rng.chance(50) {
+                        lines.swap(i, j);
+                    } else {
+                        let line = lines[i].clone();
+                        lines.insert(j, line);
+                    }
+                }
+                chars = lines.join("\n").chars().collect();
+                chars.push('\n');
+            }
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Random text from the characters puzzle inputs are made of.
+fn garbage(rng: &mut Rng) -> String {
+    let len = rng.below(60);
+    (0..len)
+        .map(|_| {
+            if rng.chance(30) {
+                rng.pick(TOKENS).to_string()
+            } else {
+                char::from(rng.range(32..=126) as u8).to_string()
+            }
+        })
+        .collect()
+}
+
+/// Parses `iterations` inputs for `day` and returns the first that panics.
+pub fn fuzz_day(day: &Day, seed: u64, iterations: usize) -> Option<Crash> {
+    let mut rng = Rng::new(seed ^ u64::from(day.number) << 32);
+    let corpus = corpus(day.number, &mut rng);
+    (0..iterations).find_map(|_| {
+        let input = if corpus.is_empty() || rng.chance(10) {
+            garbage(&mut rng)
+        } else {
+            let base = rng.pick(&corpus).clone();
+            mutate(&mut rng, &base)
+        };
+        parse_panics(day, &input).map(|message| Crash {
+            day: day.number,
+            input,
+            message,
+        })
+    })
+}
+
+/// Saves `crash` in `dir` under a name derived from its input, so the same
+/// crash is only saved once.
+pub fn save_crash(crash: &Crash, dir: &Path) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Could not create {}", dir.display()))?;
+    let path = dir.join(format!("day{}_{}.txt", crash.day, input_hash(&crash.input)));
+    fs::write(&path, &crash.input)
+        .with_context(|| format!("Could not write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Every saved crash, as the day it crashed and the input.
+pub fn saved_crashes(dir: &Path) -> Result<Vec<(u8, String)>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut crashes = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("Could not read {}", dir.display()))? {
+        let path = entry?.path();
+        let day = path
+            .file_stem()
+            .and_then(|stem| stem.to_str()?.strip_prefix("day")?.split('_').next())
+            .and_then(|day| day.parse().ok());
+        if let Some(day) = day {
+            let input = fs::read_to_string(&path)
+                .with_context(|| format!("Could not read {}", path.display()))?;
+            crashes.push((day, input));
+        }
+    }
+    crashes.sort();
+    Ok(crashes)
+}
+
+#[test]
+pub fn test_mutate_is_reproducible() {
+    let input = "move 1 from 2 to 1\nmove 3 from 1 to 3\n";
+    let mutants = |seed| {
+        let mut rng = Rng::new(seed);
+        (0..20).map(|_| mutate(&mut rng, input)).collect::<Vec<_>>()
+    };
+    assert_eq!(mutants(3), mutants(3));
+    assert!(mutants(3).iter().any(|mutant| mutant != input));
+}
+
+#[test]
+pub fn test_parsers_never_panic() {
+    for day in crate::DAYS {
+        if let Some(crash) = fuzz_day(day, 0, 500) {
+            panic!(
+                "day {} panicked with {} on {:?}",
+                crash.day, crash.message, crash.input
+            );
+        }
+    }
+}
+
+#[test]
+pub fn test_saved_crashes_do_not_panic() {
+    for (day, input) in saved_crashes(Path::new(CRASH_DIR)).unwrap() {
+        let day = crate::find_day(day).unwrap();
+        assert_eq!(
+            parse_panics(day, &input),
+            None,
+            "day {}: {:?}",
+            day.number,
+            input
+        );
+    }
+}
+
+#[test]
+pub fn test_save_crash() {
+    let dir = crate::stub::temp_dir("fuzz");
+    let crash = Crash {
+        day: 5,
+        input: "move 1 from\n".to_string(),
+        message: "oops".to_string(),
+    };
+    let path = save_crash(&crash, &dir).unwrap();
+    assert_eq!(save_crash(&crash, &dir).unwrap(), path);
+    assert_eq!(
+        saved_crashes(&dir).unwrap(),
+        vec![(5, "move 1 from\n".to_string())]
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+pub fn test_fuzz_finds_panics() {
+    use crate::solution::{Answer, Solution};
+
+    struct Fragile;
+    impl Solution for Fragile {
+        type Input = ();
+
+        fn parse(input: &str) -> Result<()> {
+            assert!(!input.contains('-'), "negative calories");
+            Ok(())
+        }
+        fn part1(_: &()) -> Result<Answer> {
+            Ok(Answer::from(0))
+        }
+        fn part2(_: &()) -> Result<Answer> {
+            Ok(Answer::from(0))
+        }
+    }
+
+    let day = Day {
+        number: 1,
+        solver: &Fragile,
+    };
+    let crash = fuzz_day(&day, 0, 1000).unwrap();
+    assert!(crash.input.contains('-'));
+    assert_eq!(crash.message, "negative calories");
+}
@@ -0,0 +1,760 @@
+//! Random but valid puzzle inputs for every solved day, to stress the solvers
+//! with inputs far larger than the real ones. The same seed and size always
+//! give the same input.
+
+use std::ops::RangeInclusive;
+
+use crate::{point::Point, solution::Params};
+
+/// SplitMix64, small and fixed so a seed gives the same input on every
+/// machine and every version of the dependencies.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A value in `range`, both ends included.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = range.into_inner();
+        assert!(start <= end, "empty range {}..={}", start, end);
+        let span = end.abs_diff(start) + 1;
+        start.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    /// An index below `n`, which must not be 0.
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0..=n as i64 - 1) as usize
+    }
+
+    pub fn chance(&mut self, percent: u32) -> bool {
+        self.next_u64() % 100 < u64::from(percent)
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// Makes an input for one day. What `size` counts differs per day, but
+/// doubling it roughly doubles the input.
+pub struct Generator {
+    pub day: u8,
+    /// What `size` means for this day, for `aoc generate --help` and reports.
+    pub size: &'static str,
+    pub generate: fn(&mut Rng, usize) -> String,
+    /// The [`Params`] to parse an input of `size` with.
+    pub params: fn(usize) -> Params,
+}
+
+pub static GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        size: "elves",
+        generate: calories,
+        params: no_params,
+    },
+    Generator {
+        day: 2,
+        size: "rounds",
+        generate: strategy_guide,
+        params: no_params,
+    },
+    Generator {
+        day: 3,
+        size: "rucksacks",
+        generate: rucksacks,
+        params: no_params,
+    },
+    Generator {
+        day: 4,
+        size: "pairs, and the highest section",
+        generate: assignments,
+        params: no_params,
+    },
+    Generator {
+        day: 5,
+        size: "moves",
+        generate: crate_moves,
+        params: no_params,
+    },
+    Generator {
+        day: 6,
+        size: "characters before the markers",
+        generate: datastream,
+        params: no_params,
+    },
+    Generator {
+        day: 8,
+        size: "trees along a side",
+        generate: trees,
+        params: no_params,
+    },
+    Generator {
+        day: 9,
+        size: "moves",
+        generate: rope_moves,
+        params: no_params,
+    },
+    Generator {
+        day: 10,
+        size: "instructions, at least 240",
+        generate: program,
+        params: no_params,
+    },
+    Generator {
+        day: 11,
+        size: "items",
+        generate: monkeys,
+        params: no_params,
+    },
+    Generator {
+        day: 12,
+        size: "columns",
+        generate: heightmap,
+        params: no_params,
+    },
+    Generator {
+        day: 13,
+        size: "packet pairs",
+        generate: packet_pairs,
+        params: no_params,
+    },
+    Generator {
+        day: 14,
+        size: "rock paths",
+        generate: rock_paths,
+        params: no_params,
+    },
+    Generator {
+        day: 15,
+        size: "sensors, up to 20 in the example's 0..=20 area",
+        generate: sensors,
+        params: sensor_params,
+    },
+];
+
+pub fn find_generator(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.day == day)
+}
+
+/// One line per item, with the trailing newline inputs have.
+fn lines(items: impl IntoIterator<Item = String>) -> String {
+    items.into_iter().map(|line| line + "\n").collect()
+}
+
+fn calories(rng: &mut Rng, size: usize) -> String {
+    let elves: Vec<String> = (0..size.max(3))
+        .map(|_| {
+            let items = rng.range(1..=15);
+            lines((0..items).map(|_| rng.range(1000..=60000).to_string()))
+        })
+        .collect();
+    elves.join("\n")
+}
+
+fn strategy_guide(rng: &mut Rng, size: usize) -> String {
+    lines((0..size.max(1)).map(|_| {
+        format!(
+            "{} {}",
+            rng.pick(&['A', 'B', 'C']),
+            rng.pick(&['X', 'Y', 'Z'])
+        )
+    }))
+}
+
+/// Every elf of a group draws from its own third of the letters, so only the
+/// badge is common to the group, and its halves from their own sixths, so
+/// only the one shared item is common to both.
+fn rucksacks(rng: &mut Rng, size: usize) -> String {
+    let letters: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut rucksacks = Vec::new();
+    for _ in 0..size.div_ceil(3).max(1) {
+        let mut pool = letters.clone();
+        rng.shuffle(&mut pool);
+        let badge = pool.pop().unwrap();
+        for own in pool.chunks(pool.len() / 3) {
+            rucksacks.push(rucksack(rng, badge, own));
+        }
+    }
+    lines(rucksacks)
+}
+
+fn rucksack(rng: &mut Rng, badge: char, own: &[char]) -> String {
+    let shared = if rng.chance(20) {
+        badge
+    } else {
+        *rng.pick(own)
+    };
+    let rest: Vec<char> = own.iter().copied().filter(|&c| c != shared).collect();
+    let (left_pool, right_pool) = rest.split_at(rest.len() / 2);
+
+    let half = rng.range(4..=16) as usize;
+    let mut left = vec![shared];
+    if shared != badge {
+        left.push(badge);
+    }
+    let mut right = vec![shared];
+    while left.len() < half {
+        left.push(*rng.pick(left_pool));
+    }
+    while right.len() < half {
+        right.push(*rng.pick(right_pool));
+    }
+    rng.shuffle(&mut left);
+    rng.shuffle(&mut right);
+    left.into_iter().chain(right).collect()
+}
+
+/// Section ids go up to `size`, but never below the real input's 99.
+fn assignments(rng: &mut Rng, size: usize) -> String {
+    let highest = (size as i64).max(99);
+    lines((0..size.max(1)).map(|_| {
+        let first = section_range(rng, 1, highest);
+        let second = if rng.chance(30) {
+            section_range(rng, first.0, first.1)
+        } else {
+            section_range(rng, 1, highest)
+        };
+        let (first, second) = if rng.chance(50) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        format!("{}-{},{}-{}", first.0, first.1, second.0, second.1)
+    }))
+}
+
+fn section_range(rng: &mut Rng, lowest: i64, highest: i64) -> (i64, i64) {
+    let start = rng.range(lowest..=highest);
+    (start, rng.range(start..=highest))
+}
+
+/// Moves are played while generating so none takes more crates than its
+/// stack holds.
+fn crate_moves(rng: &mut Rng, size: usize) -> String {
+    let count = rng.range(3..=9) as usize;
+    let tallest = 8 + size as i64 / 50;
+    let mut stacks: Vec<Vec<char>> = (0..count)
+        .map(|_| {
+            (0..rng.range(1..=tallest))
+                .map(|_| (b'A' + rng.below(26) as u8) as char)
+                .collect()
+        })
+        .collect();
+
+    let height = stacks.iter().map(Vec::len).max().unwrap();
+    let mut drawing: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            let row: Vec<String> = stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(container) => format!("[{}]", container),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            row.join(" ")
+        })
+        .collect();
+    let numbers: Vec<String> = (1..=count).map(|stack| format!(" {} ", stack)).collect();
+    drawing.push(numbers.join(" "));
+
+    let moves = (0..size.max(1)).map(|_| {
+        let filled: Vec<usize> = (0..count).filter(|&i| !stacks[i].is_empty()).collect();
+        let from = *rng.pick(&filled);
+        let to = (from + 1 + rng.below(count - 1)) % count;
+        let amount = rng.range(1..=stacks[from].len().min(255) as i64) as usize;
+        let at = stacks[from].len() - amount;
+        let moved: Vec<char> = stacks[from].drain(at..).collect();
+        stacks[to].extend(moved);
+        format!("move {} from {} to {}", amount, from + 1, to + 1)
+    });
+    let moves: Vec<String> = moves.collect();
+    lines(drawing) + "\n" + &lines(moves)
+}
+
+/// Three letters cannot hold a start-of-packet marker and thirteen cannot
+/// hold a start-of-message marker, so each marker shows up after its noise.
+fn datastream(rng: &mut Rng, size: usize) -> String {
+    let mut letters: Vec<char> = ('a'..='z').collect();
+    rng.shuffle(&mut letters);
+    let mut stream = String::new();
+    stream.extend((0..size).map(|_| *rng.pick(&letters[..3])));
+    stream.extend((0..size).map(|_| *rng.pick(&letters[..13])));
+    rng.shuffle(&mut letters);
+    stream.extend(&letters[..14]);
+    stream.extend((0..size / 2 + 10).map(|_| *rng.pick(&letters)));
+    stream + "\n"
+}
+
+fn trees(rng: &mut Rng, size: usize) -> String {
+    let side = size.max(1);
+    lines((0..side).map(|_| {
+        (0..side)
+            .map(|_| char::from(b'0' + rng.below(10) as u8))
+            .collect()
+    }))
+}
+
+fn rope_moves(rng: &mut Rng, size: usize) -> String {
+    lines(
+        (0..size.max(1))
+            .map(|_| format!("{} {}", rng.pick(&['U', 'D', 'L', 'R']), rng.range(1..=19))),
+    )
+}
+
+/// `size` instructions with the register kept near the screen like in the
+/// real programs. Sizes below 240 are raised to 240, so the program runs the
+/// 240 cycles day 10 reads even when every instruction is a `noop`.
+fn program(rng: &mut Rng, size: usize) -> String {
+    let mut register = 1;
+    let mut instructions = Vec::new();
+    for _ in 0..size.max(240) {
+        if rng.chance(40) {
+            instructions.push("noop".to_string());
+        } else {
+            let mut value = rng.range(-10..=10);
+            if !(-2..=42).contains(&(register + value)) {
+                value = -value;
+            }
+            register += value;
+            instructions.push(format!("addx {}", value));
+        }
+    }
+    lines(instructions)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Worry {
+    Add(u64),
+    Multiply(u64),
+    Square,
+}
+
+impl Worry {
+    fn apply(self, old: u64) -> Option<u64> {
+        match self {
+            Worry::Add(value) => old.checked_add(value),
+            Worry::Multiply(value) => old.checked_mul(value),
+            Worry::Square => old.checked_mul(old),
+        }
+    }
+}
+
+struct MonkeySpec {
+    items: Vec<u64>,
+    worry: Worry,
+    divisor: u64,
+    targets: (usize, usize),
+}
+
+/// Divisors come from the first primes, so part 2's modulus stays small
+/// enough to square in a u64.
+const DIVISORS: [u64; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+/// Part 1 keeps every worry level, so layouts whose levels would overflow in
+/// its 20 rounds are thrown away, with fewer multiplications each attempt.
+fn monkeys(rng: &mut Rng, size: usize) -> String {
+    let count = (size / 4).clamp(2, 40);
+    let items = size.max(count);
+    let mut attempt = 0;
+    let monkeys = loop {
+        let monkeys = monkey_layout(rng, count, items, attempt);
+        if part1_fits(&monkeys) {
+            break monkeys;
+        }
+        attempt += 1;
+    };
+
+    let blocks: Vec<String> = monkeys
+        .iter()
+        .enumerate()
+        .map(|(i, monkey)| {
+            let items: Vec<String> = monkey.items.iter().map(u64::to_string).collect();
+            let operation = match monkey.worry {
+                Worry::Add(value) => format!("old + {}", value),
+                Worry::Multiply(value) => format!("old * {}", value),
+                Worry::Square => "old * old".to_string(),
+            };
+            format!(
+                "Monkey {}:\n  Starting items: {}\n  Operation: new = {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+                i,
+                items.join(", "),
+                operation,
+                monkey.divisor,
+                monkey.targets.0,
+                monkey.targets.1
+            )
+        })
+        .collect();
+    blocks.join("\n")
+}
+
+fn monkey_layout(rng: &mut Rng, count: usize, items: usize, attempt: u32) -> Vec<MonkeySpec> {
+    let multiply = 30u32.saturating_sub(attempt * 3);
+    let square = attempt < 5;
+    let mut divisors = DIVISORS.to_vec();
+    rng.shuffle(&mut divisors);
+
+    let mut monkeys: Vec<MonkeySpec> = (0..count)
+        .map(|i| {
+            let worry = if i == 0 && square {
+                Worry::Square
+            } else if rng.chance(multiply) {
+                Worry::Multiply(rng.range(2..=19) as u64)
+            } else {
+                Worry::Add(rng.range(1..=8) as u64)
+            };
+            let other = |rng: &mut Rng| (i + 1 + rng.below(count - 1)) % count;
+            let when_true = other(rng);
+            let mut when_false = other(rng);
+            while count > 2 && when_false == when_true {
+                when_false = other(rng);
+            }
+            MonkeySpec {
+                items: vec![rng.range(50..=99) as u64],
+                worry,
+                divisor: divisors[i % divisors.len()],
+                targets: (when_true, when_false),
+            }
+        })
+        .collect();
+    for _ in count..items {
+        let monkey = rng.below(count);
+        monkeys[monkey].items.push(rng.range(50..=99) as u64);
+    }
+    monkeys
+}
+
+fn part1_fits(monkeys: &[MonkeySpec]) -> bool {
+    let mut items: Vec<Vec<u64>> = monkeys.iter().map(|monkey| monkey.items.clone()).collect();
+    for _ in 0..20 {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for item in std::mem::take(&mut items[i]) {
+                let Some(worry) = monkey.worry.apply(item) else {
+                    return false;
+                };
+                let worry = worry / 3;
+                let target = if worry % monkey.divisor == 0 {
+                    monkey.targets.0
+                } else {
+                    monkey.targets.1
+                };
+                items[target].push(worry);
+            }
+        }
+    }
+    true
+}
+
+/// A path that climbs one elevation at a time from `S` to `E` is drawn first,
+/// so `E` is always reachable. The rest of the map is random.
+fn heightmap(rng: &mut Rng, size: usize) -> String {
+    let width = size.max(30);
+    let height = (size / 3).max(5);
+    let mut map: Vec<Vec<char>> = (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| (b'a' + rng.below(26) as u8) as char)
+                .collect()
+        })
+        .collect();
+
+    let mut y = rng.below(height);
+    let mut path = Vec::new();
+    for x in 0..width {
+        let target = rng.below(height);
+        while y != target {
+            path.push((x, y));
+            y = if target > y { y + 1 } else { y - 1 };
+        }
+        path.push((x, y));
+    }
+    let last = path.len() - 1;
+    for (i, &(x, y)) in path.iter().enumerate() {
+        map[y][x] = (b'a' + (i * 25 / last) as u8) as char;
+    }
+    let (start, end) = (path[0], path[last]);
+    map[start.1][start.0] = 'S';
+    map[end.1][end.0] = 'E';
+    lines(map.into_iter().map(String::from_iter))
+}
+
+fn packet_pairs(rng: &mut Rng, size: usize) -> String {
+    let pairs: Vec<String> = (0..size.max(1))
+        .map(|_| {
+            let left = packet(rng, 0);
+            let mut right = packet(rng, 0);
+            while right == left {
+                right = packet(rng, 0);
+            }
+            format!("{}\n{}\n", left, right)
+        })
+        .collect();
+    pairs.join("\n")
+}
+
+fn packet(rng: &mut Rng, depth: u32) -> String {
+    let values: Vec<String> = (0..rng.range(0..=5))
+        .map(|_| {
+            if depth < 4 && rng.chance(30) {
+                packet(rng, depth + 1)
+            } else {
+                rng.range(0..=10).to_string()
+            }
+        })
+        .collect();
+    format!("[{}]", values.join(","))
+}
+
+/// Rocks stay within `depth` of the source on both sides and below it, and
+/// the first path runs under the source so the cave spans it.
+fn rock_paths(rng: &mut Rng, size: usize) -> String {
+    let depth = (10 + size as i64).min(400);
+    let (left, right) = (500 - depth, 500 + depth);
+    let floor = rng.range(depth / 2..=depth);
+    let mut paths = vec![format!(
+        "{},{} -> {},{}",
+        rng.range(left..=499),
+        floor,
+        rng.range(501..=right),
+        floor
+    )];
+
+    for _ in 1..size.max(1) {
+        let (mut x, mut y) = (rng.range(left..=right), rng.range(1..=depth));
+        let mut points = vec![format!("{},{}", x, y)];
+        let mut horizontal = rng.chance(50);
+        for _ in 0..rng.range(1..=5) {
+            let length = rng.range(1..=6);
+            let step = if rng.chance(50) { length } else { -length };
+            if horizontal {
+                x = (x + step).clamp(left, right);
+            } else {
+                y = (y + step).clamp(1, depth);
+            }
+            horizontal = !horizontal;
+            points.push(format!("{},{}", x, y));
+        }
+        paths.push(points.join(" -> "));
+    }
+    rng.shuffle(&mut paths);
+    lines(paths)
+}
+
+/// Up to 20 sensors ask about the example's area, more the real puzzle's.
+fn sensors(rng: &mut Rng, size: usize) -> String {
+    let (mut field, _) = sensor_field(rng, size);
+    rng.shuffle(&mut field);
+    lines(field.into_iter().map(|(sensor, beacon)| {
+        format!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            sensor.x, sensor.y, beacon.x, beacon.y
+        )
+    }))
+}
+
+fn sensor_limit(size: usize) -> i64 {
+    if size.max(1) <= 20 {
+        20
+    } else {
+        4_000_000
+    }
+}
+
+fn sensor_params(size: usize) -> Params {
+    let limit = sensor_limit(size);
+    Params::new().with("row", limit / 2).with("limit", limit)
+}
+
+fn no_params(_: usize) -> Params {
+    Params::new()
+}
+
+/// Sensors and their beacons that leave exactly one position of the search
+/// area uncovered, which is returned as well.
+///
+/// Every sensor reaches one short of the gap. Four sensors diagonally away
+/// from the gap cover the area around it, and a lattice of sensors whose
+/// diamonds tile the plane covers the rest.
+fn sensor_field(rng: &mut Rng, size: usize) -> (Vec<(Point, Point)>, Point) {
+    let count = size.max(1);
+    let limit = sensor_limit(size);
+    let gap = Point::new(rng.range(0..=limit) as i32, rng.range(0..=limit) as i32);
+    let tile = ((limit as f64 / (2.0 * count as f64).sqrt()) as i64).max(2);
+
+    let mut positions = Vec::new();
+    for (dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let offset = Point::new(
+            (dx * rng.range(2 * tile..=3 * tile)) as i32,
+            (dy * rng.range(2 * tile..=3 * tile)) as i32,
+        );
+        positions.push(gap + offset);
+    }
+
+    // In coordinates turned 45 degrees the lattice is a square grid with
+    // cells 2 * tile wide, so every position is within `tile` of a sensor.
+    let origin = Point::new(
+        rng.range(0..=tile - 1) as i32,
+        rng.range(0..=tile - 1) as i32,
+    );
+    let reach = (limit + 3 * tile) / tile + 1;
+    for a in -reach..=reach {
+        for b in -reach..=reach {
+            if (a - b) % 2 != 0 {
+                continue;
+            }
+            let sensor = origin + Point::new((a * tile) as i32, (b * tile) as i32);
+            let near_area = Point::new(
+                sensor.x.clamp(0, limit as i32),
+                sensor.y.clamp(0, limit as i32),
+            )
+            .manhattan(sensor)
+                <= tile as i32;
+            if near_area && sensor.manhattan(gap) > tile as i32 && !positions.contains(&sensor) {
+                positions.push(sensor);
+            }
+        }
+    }
+
+    let radii: Vec<i32> = positions
+        .iter()
+        .map(|sensor| sensor.manhattan(gap) - 1)
+        .collect();
+    let field = positions
+        .iter()
+        .zip(&radii)
+        .map(|(&sensor, &radius)| {
+            // A beacon may not sit inside another sensor's reach, or that
+            // sensor would have found it first. Next to the gap never is.
+            let fits = |beacon: Point| {
+                positions
+                    .iter()
+                    .zip(&radii)
+                    .all(|(&other, &reach)| other.manhattan(beacon) >= reach)
+            };
+            let beacon = (0..8)
+                .map(|_| diamond_point(sensor, radius, rng.below(4 * radius as usize)))
+                .find(|&beacon| fits(beacon))
+                .unwrap_or_else(|| {
+                    let toward = (sensor - gap).signum();
+                    gap + if toward.x != 0 {
+                        Point::new(toward.x, 0)
+                    } else {
+                        Point::new(0, toward.y)
+                    }
+                });
+            (sensor, beacon)
+        })
+        .collect();
+    (field, gap)
+}
+
+/// The `step`th of the `4 * radius` positions exactly `radius` from `center`.
+fn diamond_point(center: Point, radius: i32, step: usize) -> Point {
+    let (side, along) = (step as i32 / radius, step as i32 % radius);
+    let offset = match side {
+        0 => Point::new(along, along - radius),
+        1 => Point::new(radius - along, along),
+        2 => Point::new(-along, radius - along),
+        _ => Point::new(along - radius, -along),
+    };
+    center + offset
+}
+
+#[test]
+pub fn test_rng_is_reproducible() {
+    let mut a = Rng::new(7);
+    let mut b = Rng::new(7);
+    let first: Vec<u64> = (0..5).map(|_| a.next_u64()).collect();
+    assert_eq!(first, (0..5).map(|_| b.next_u64()).collect::<Vec<_>>());
+    assert_ne!(first[0], Rng::new(8).next_u64());
+    assert!((0..1000).all(|_| (-3..=3).contains(&a.range(-3..=3))));
+}
+
+#[test]
+pub fn test_generators_are_reproducible() {
+    for generator in GENERATORS {
+        let input = |seed| (generator.generate)(&mut Rng::new(seed), 30);
+        assert_eq!(input(1), input(1), "day {}", generator.day);
+        assert_ne!(input(1), input(2), "day {}", generator.day);
+    }
+}
+
+#[test]
+pub fn test_generated_inputs_solve() {
+    use crate::{find_day, solution::Part};
+
+    for generator in GENERATORS {
+        let day = find_day(generator.day).unwrap();
+        for (seed, size) in [(0, 1), (1, 10), (2, 60)] {
+            let input = (generator.generate)(&mut Rng::new(seed), size);
+            let parsed = day
+                .solver
+                .parse_with(&input, &(generator.params)(size))
+                .unwrap_or_else(|err| panic!("day {} size {}: {:#}", generator.day, size, err));
+            for part in Part::ALL {
+                day.solver
+                    .solve(parsed.as_ref(), part)
+                    .unwrap_or_else(|err| panic!("day {} size {}: {:#}", generator.day, size, err));
+            }
+        }
+    }
+}
+
+#[test]
+pub fn test_program_size() {
+    let length = |size| program(&mut Rng::new(3), size).lines().count();
+    assert_eq!(length(1), 240);
+    assert_eq!(length(240), 240);
+    assert_eq!(length(300), 300);
+}
+
+#[test]
+pub fn test_diamond_point() {
+    let center = Point::new(3, -2);
+    let ring: std::collections::HashSet<Point> =
+        (0..12).map(|step| diamond_point(center, 3, step)).collect();
+    assert_eq!(ring.len(), 12);
+    assert!(ring.iter().all(|point| point.manhattan(center) == 3));
+}
+
+#[test]
+pub fn test_sensor_field_has_one_gap() {
+    for (seed, size) in [(0, 1), (1, 5), (2, 14), (3, 20)] {
+        let (field, gap) = sensor_field(&mut Rng::new(seed), size);
+        let covered = |point: Point| {
+            field
+                .iter()
+                .any(|&(sensor, beacon)| sensor.manhattan(point) <= sensor.manhattan(beacon))
+        };
+        let uncovered: Vec<Point> = (0..=20)
+            .flat_map(|y| (0..=20).map(move |x| Point::new(x, y)))
+            .filter(|&point| !covered(point))
+            .collect();
+        assert_eq!(uncovered, vec![gap], "seed {} size {}", seed, size);
+        // No beacon is closer to a sensor than the one it reports.
+        assert!(field.iter().all(|&(_, beacon)| field
+            .iter()
+            .all(|&(other, closest)| other.manhattan(beacon) >= other.manhattan(closest))));
+    }
+}
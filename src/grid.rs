@@ -0,0 +1,260 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    error::ParseError,
+    point::{Direction, Point},
+};
+
+/// A cell position, `(x, y)` with `y` counting rows down from the top.
+pub type Pos = (usize, usize);
+
+/// A rectangular grid stored row by row in one `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Panics if `width * height` does not fit in a `usize`.
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        let size = width
+            .checked_mul(height)
+            .unwrap_or_else(|| panic!("a {}x{} grid is too large", width, height));
+        Grid {
+            width,
+            height,
+            cells: vec![fill; size],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Reads a char map, one row per line. `cell` turns a char into a value,
+    /// `None` fails with `expected` pointing at the char.
+    pub fn parse(
+        input: &str,
+        expected: &str,
+        cell: impl Fn(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let mut cells = Vec::new();
+        let mut width = None;
+        let mut height = 0;
+        for (row, line) in input.lines().enumerate() {
+            for (column, char) in line.chars().enumerate() {
+                cells.push(cell(char).ok_or_else(|| {
+                    ParseError::new(expected, line)
+                        .at_line(row + 1)
+                        .at_column(column + 1)
+                })?);
+            }
+            let line_width = line.chars().count();
+            if line_width == 0 || *width.get_or_insert(line_width) != line_width {
+                return Err(ParseError::new("expected rows of equal width", line).at_line(row + 1));
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) => Ok(Grid {
+                width,
+                height,
+                cells,
+            }),
+            None => Err(ParseError::new("expected a grid", "")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Pos) -> Option<&T> {
+        self.index_of(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, (x, y): Pos) -> Option<&mut T> {
+        self.index_of(x, y).map(|i| &mut self.cells[i])
+    }
+
+    fn index_of(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    /// `pos` moved by `offset`, if that is still inside the grid.
+    pub fn step(&self, (x, y): Pos, offset: Point) -> Option<Pos> {
+        let next = (
+            x.checked_add_signed(offset.x as isize)?,
+            y.checked_add_signed(offset.y as isize)?,
+        );
+        self.index_of(next.0, next.1).map(|_| next)
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Left to right, empty when `y` is outside the grid.
+    pub fn row(&self, y: usize) -> &[T] {
+        if y < self.height {
+            &self.cells[y * self.width..(y + 1) * self.width]
+        } else {
+            &[]
+        }
+    }
+
+    /// Top to bottom, empty when `x` is outside the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let height = if x < self.width { self.height } else { 0 };
+        (0..height).map(move |y| &self.cells[y * self.width + x])
+    }
+
+    /// The positions up, right, down and left of `pos` that are in the grid.
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(pos, direction.offset()))
+    }
+
+    /// [`Grid::neighbours4`] plus the diagonals.
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Point::AROUND
+            .into_iter()
+            .filter_map(move |offset| self.step(pos, offset))
+    }
+
+    /// The cells from `pos` (not included) to the edge, moving by `offset`.
+    pub fn ray(&self, pos: Pos, offset: Point) -> impl Iterator<Item = (Pos, &T)> {
+        let mut pos = Some(pos);
+        std::iter::from_fn(move || {
+            pos = self.step(pos?, offset);
+            pos.map(|pos| (pos, &self[pos]))
+        })
+    }
+
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .find(|(_, cell)| *cell == value)
+            .map(|(pos, _)| pos)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{:?} is outside the grid", pos))
+    }
+}
+
+/// One line per row, cells written next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(y) {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+fn example() -> Grid<u32> {
+    Grid::parse("123\n456\n", "expected a digit", |c| c.to_digit(10)).unwrap()
+}
+
+#[test]
+pub fn test_parse() {
+    let grid = example();
+    assert_eq!((grid.width(), grid.height()), (3, 2));
+    assert_eq!(grid[(2, 1)], 6);
+    assert_eq!(grid.get((3, 0)), None);
+    assert_eq!(grid.to_string(), "123\n456");
+
+    let err = Grid::parse("12\n3x\n", "expected a digit", |c| c.to_digit(10)).unwrap_err();
+    assert_eq!((err.line, err.column), (Some(2), Some(2)));
+    let err = Grid::parse("12\n3\n", "expected a digit", |c| c.to_digit(10)).unwrap_err();
+    assert_eq!(err.line, Some(2));
+    assert!(Grid::parse("", "expected a digit", |c| c.to_digit(10)).is_err());
+}
+
+#[test]
+pub fn test_neighbours() {
+    let grid = example();
+    assert_eq!(
+        grid.neighbours4((0, 0)).collect::<Vec<_>>(),
+        vec![(1, 0), (0, 1)]
+    );
+    assert_eq!(grid.neighbours8((1, 0)).count(), 5);
+    assert_eq!(grid.neighbours8((1, 1)).count(), 5);
+}
+
+#[test]
+pub fn test_rays() {
+    let grid = example();
+    let values = |direction: Direction| {
+        grid.ray((1, 1), direction.offset())
+            .map(|(_, &value)| value)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(values(Direction::Left), vec![4]);
+    assert_eq!(values(Direction::Right), vec![6]);
+    assert_eq!(values(Direction::Up), vec![2]);
+    assert_eq!(values(Direction::Down), Vec::<u32>::new());
+    assert_eq!(grid.row(1), &[4, 5, 6]);
+    assert!(grid.row(2).is_empty());
+    assert_eq!(grid.column(2).copied().collect::<Vec<_>>(), vec![3, 6]);
+    assert_eq!(grid.column(3).count(), 0);
+    assert_eq!(Grid::new(0, 2, 0).column(0).count(), 0);
+}
+
+#[test]
+pub fn test_find_and_map() {
+    let grid = example();
+    assert_eq!(grid.find(&5), Some((1, 1)));
+    assert_eq!(grid.find(&7), None);
+    assert_eq!(grid.map(|&value| value * 2)[(0, 1)], 8);
+}
+
+#[test]
+#[should_panic(expected = "too large")]
+pub fn test_new_overflow() {
+    Grid::new(usize::MAX, 2, false);
+}
@@ -1,36 +1,116 @@
 use std::{
-    fs::{self, File},
-    io::{BufRead, BufReader},
+    any::Any,
+    env, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
 };
 
-pub fn read_lines(day: i32, test: bool) -> Vec<String> {
-    let file = File::open(format!(
-        "resources/day{}{}.txt",
-        day,
-        if test { "_test" } else { "" }
-    ))
-    .unwrap();
-    let reader = BufReader::new(file);
-    reader.lines().filter_map(std::io::Result::ok).collect()
-}
-pub fn read_string(day: i32, test: bool) -> String {
-    let path = format!(
-        "resources/day{}{}.txt",
-        day,
-        if test { "_test" } else { "" }
-    );
-    fs::read_to_string(path).unwrap()
-}
-
-#[derive(Debug)]
-pub struct ParseError {
-    details: String,
-}
-
-impl ParseError {
-    pub fn new(msg: &str) -> ParseError {
-        ParseError {
-            details: msg.to_string(),
-        }
+use anyhow::{Context, Result};
+
+/// Overrides the directory `dayN.txt` and `dayN_test.txt` are read from.
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+const DEFAULT_INPUT_DIR: &str = "resources";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    /// `dayN.txt`, or `dayN_test.txt` for the example, in the input directory
+    Day {
+        day: u8,
+        test: bool,
+    },
+    Path(PathBuf),
+    Stdin,
+}
+
+impl Input {
+    /// Reads the whole input with `\n` line endings and exactly one trailing newline.
+    pub fn load(&self) -> Result<String> {
+        let raw = match self {
+            Input::Day { day, test } => read_file(&input_path(*day, *test))?,
+            Input::Path(path) => read_file(path)?,
+            Input::Stdin => {
+                let mut raw = String::new();
+                io::stdin()
+                    .read_to_string(&mut raw)
+                    .context("Could not read input from stdin")?;
+                raw
+            }
+        };
+        Ok(normalize(&raw))
     }
 }
+
+pub fn input_dir() -> PathBuf {
+    env::var_os(INPUT_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_INPUT_DIR))
+}
+
+/// File stem of a day's input, `day12` or `day12_test`.
+pub fn input_name(day: u8, test: bool) -> String {
+    format!("day{}{}", day, if test { "_test" } else { "" })
+}
+
+pub fn input_path(day: u8, test: bool) -> PathBuf {
+    input_dir().join(format!("{}.txt", input_name(day, test)))
+}
+
+pub fn normalize(raw: &str) -> String {
+    let mut input = raw.replace("\r\n", "\n");
+    input.truncate(input.trim_end_matches('\n').len());
+    input.push('\n');
+    input
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))
+}
+
+pub fn read_lines(day: u8, test: bool) -> Result<Vec<String>> {
+    Ok(read_string(day, test)?
+        .lines()
+        .map(|line| line.to_owned())
+        .collect())
+}
+pub fn read_string(day: u8, test: bool) -> Result<String> {
+    Input::Day { day, test }.load()
+}
+
+/// A short fingerprint of an input, FNV-1a in hex, to tell inputs apart
+/// without showing them.
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325, |hash: u64, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("{:016x}", hash)
+}
+
+/// The message of a panic caught with `catch_unwind`.
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "without a message".to_string())
+}
+
+#[test]
+pub fn test_normalize() {
+    assert_eq!(normalize("a\r\nb\r\n\r\n"), "a\nb\n");
+    assert_eq!(normalize("  a\n\nb"), "  a\n\nb\n");
+}
+
+#[test]
+pub fn test_missing_input() {
+    let err = Input::Path(PathBuf::from("resources/day99.txt"))
+        .load()
+        .unwrap_err();
+    assert!(err.to_string().contains("day99.txt"));
+}
+
+#[test]
+pub fn test_input_hash() {
+    assert_eq!(input_hash(""), "cbf29ce484222325");
+    assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+    assert_ne!(input_hash("1\n2\n"), input_hash("2\n1\n"));
+}
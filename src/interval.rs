@@ -0,0 +1,255 @@
+use std::fmt;
+
+/// The integers from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    start: i64,
+    end: i64,
+}
+
+impl Interval {
+    /// None if `start` is after `end`, an interval always holds a value.
+    pub fn new(start: i64, end: i64) -> Option<Interval> {
+        (start <= end).then_some(Interval { start, end })
+    }
+
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Wider than `i64`, as `i64::MIN..=i64::MAX` holds 2^64 values.
+    // Never empty, so there is no `is_empty`.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u128 {
+        u128::from(self.end.abs_diff(self.start)) + 1
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.start <= value && value <= self.end
+    }
+
+    /// Every value of `other` is in this interval.
+    pub fn contains_interval(&self, other: &Interval) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+/// A set of integers kept as sorted, disjoint intervals. Touching intervals
+/// are merged, so `1-2` and `3-4` are stored as `1-4`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RangeSet {
+    intervals: Vec<Interval>,
+}
+
+impl RangeSet {
+    pub fn new() -> RangeSet {
+        RangeSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> u128 {
+        self.intervals.iter().map(Interval::len).sum()
+    }
+
+    pub fn insert(&mut self, interval: Interval) {
+        // Intervals before `first` end too early to touch `interval`, those
+        // from `last` on start too late.
+        let first = self
+            .intervals
+            .partition_point(|other| other.end < interval.start.saturating_sub(1));
+        let last = self
+            .intervals
+            .partition_point(|other| other.start <= interval.end.saturating_add(1));
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, other| Interval {
+                start: merged.start.min(other.start),
+                end: merged.end.max(other.end),
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// The interval holding `value`, if any.
+    fn find(&self, value: i64) -> Option<&Interval> {
+        let i = self.intervals.partition_point(|other| other.end < value);
+        self.intervals.get(i).filter(|other| other.contains(value))
+    }
+
+    pub fn contains(&self, value: i64) -> bool {
+        self.find(value).is_some()
+    }
+
+    pub fn contains_interval(&self, interval: &Interval) -> bool {
+        self.find(interval.start)
+            .is_some_and(|other| other.contains_interval(interval))
+    }
+
+    pub fn overlaps(&self, interval: &Interval) -> bool {
+        let i = self
+            .intervals
+            .partition_point(|other| other.end < interval.start);
+        self.intervals
+            .get(i)
+            .is_some_and(|other| other.overlaps(interval))
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        self.intervals
+            .iter()
+            .chain(&other.intervals)
+            .copied()
+            .collect()
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let mut result = RangeSet::new();
+        let (mut i, mut j) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(i), other.intervals.get(j)) {
+            if let Some(both) = a.intersection(b) {
+                result.intervals.push(both);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        result
+    }
+
+    /// The parts of `within` that are not in the set.
+    pub fn gaps(&self, within: Interval) -> RangeSet {
+        let mut gaps = RangeSet::new();
+        let mut next = within.start;
+        for interval in &self.intervals {
+            if interval.end < next {
+                continue;
+            }
+            if interval.start > within.end {
+                break;
+            }
+            if interval.start > next {
+                gaps.intervals.push(Interval {
+                    start: next,
+                    end: interval.start - 1,
+                });
+            }
+            next = interval.end.saturating_add(1);
+        }
+        if next <= within.end {
+            gaps.intervals.push(Interval {
+                start: next,
+                end: within.end,
+            });
+        }
+        gaps
+    }
+}
+
+/// Sorts once and merges, O(k log k) for k intervals.
+impl FromIterator<Interval> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> RangeSet {
+        let mut intervals: Vec<Interval> = iter.into_iter().collect();
+        intervals.sort_unstable();
+        let mut set = RangeSet::new();
+        for interval in intervals {
+            match set.intervals.last_mut() {
+                Some(last) if interval.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(interval.end);
+                }
+                _ => set.intervals.push(interval),
+            }
+        }
+        set
+    }
+}
+
+#[test]
+pub fn test_interval() {
+    let a = Interval::new(2, 6).unwrap();
+    let b = Interval::new(4, 8).unwrap();
+    assert_eq!(a.len(), 5);
+    assert!(a.contains(6) && !a.contains(7));
+    assert!(a.overlaps(&b));
+    assert!(!a.overlaps(&Interval::new(7, 9).unwrap()));
+    assert_eq!(a.intersection(&b), Interval::new(4, 6));
+    assert_eq!(a.intersection(&Interval::new(7, 9).unwrap()), None);
+    assert!(a.contains_interval(&Interval::new(3, 6).unwrap()));
+    assert!(!a.contains_interval(&b));
+    assert_eq!(a.to_string(), "2-6");
+    assert_eq!(Interval::new(3, 2), None);
+    assert_eq!(Interval::new(i64::MIN, i64::MAX).unwrap().len(), 1 << 64);
+}
+
+#[cfg(test)]
+fn set(intervals: &[(i64, i64)]) -> RangeSet {
+    intervals
+        .iter()
+        .map(|&(start, end)| Interval::new(start, end).unwrap())
+        .collect()
+}
+
+#[test]
+pub fn test_merge() {
+    let merged = set(&[(5, 7), (1, 2), (3, 4), (10, 12), (11, 20)]);
+    assert_eq!(merged, set(&[(1, 7), (10, 20)]));
+    assert_eq!(merged.intervals().len(), 2);
+    assert_eq!(merged.len(), 18);
+
+    let mut inserted = set(&[(1, 2), (10, 12)]);
+    inserted.insert(Interval::new(3, 9).unwrap());
+    assert_eq!(inserted, set(&[(1, 12)]));
+    inserted.insert(Interval::new(20, 21).unwrap());
+    inserted.insert(Interval::new(-5, -5).unwrap());
+    assert_eq!(inserted, set(&[(-5, -5), (1, 12), (20, 21)]));
+}
+
+#[test]
+pub fn test_set_queries() {
+    let ranges = set(&[(1, 4), (8, 10)]);
+    assert!(ranges.contains(4) && !ranges.contains(5));
+    assert!(ranges.contains_interval(&Interval::new(8, 9).unwrap()));
+    assert!(!ranges.contains_interval(&Interval::new(3, 8).unwrap()));
+    assert!(ranges.overlaps(&Interval::new(3, 8).unwrap()));
+    assert!(!ranges.overlaps(&Interval::new(5, 7).unwrap()));
+    assert_eq!(
+        ranges.gaps(Interval::new(0, 12).unwrap()),
+        set(&[(0, 0), (5, 7), (11, 12)])
+    );
+    assert_eq!(ranges.gaps(Interval::new(2, 3).unwrap()), RangeSet::new());
+}
+
+#[test]
+pub fn test_set_operations() {
+    let a = set(&[(1, 4), (8, 10)]);
+    let b = set(&[(3, 9), (20, 20)]);
+    assert_eq!(a.union(&b), set(&[(1, 10), (20, 20)]));
+    assert_eq!(a.intersection(&b), set(&[(3, 4), (8, 9)]));
+    assert_eq!(a.intersection(&RangeSet::new()), RangeSet::new());
+}
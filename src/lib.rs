@@ -1,15 +1,161 @@
-mod day1;
-mod day10;
-mod day11;
+//! Advent of Code 2022. Every day module parses its input once and answers
+//! both parts from it:
+//!
+//! ```
+//! let elves = aoc_2022::day1::parse("1000\n2000\n\n4000\n\n5000\n6000")?;
+//! assert_eq!(aoc_2022::day1::part1(&elves)?, aoc_2022::Answer::from(11000));
+//! # anyhow::Ok(())
+//! ```
+//!
+//! [`DAYS`] holds them all behind [`Solver`] for running by number.
+//!
+//! The tooling behind the `aoc` binary, like the runner, the input fetcher
+//! and the generators, comes with the `cli` feature. It is on by default,
+//! embedders that only want the solvers turn it off with
+//! `default-features = false`.
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
 pub mod day12;
-mod day13;
-mod day14;
+pub mod day13;
+pub mod day14;
 pub mod day15;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day8;
-mod day9;
-pub mod helper;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day8;
+pub mod day9;
+mod error;
+pub mod grid;
+pub mod interval;
+mod parse;
+pub mod point;
+pub mod search;
+mod solution;
+
+/// Declares modules the days use that the `aoc` binary needs as well. They
+/// are public only with the `cli` feature, without it the parts only the
+/// binary uses go unused.
+macro_rules! shared {
+    ($($name:ident),* $(,)?) => {
+        $(
+            #[cfg(feature = "cli")]
+            pub mod $name;
+            #[cfg(not(feature = "cli"))]
+            #[allow(dead_code)]
+            mod $name;
+        )*
+    };
+}
+
+shared!(helper, trace, visual);
+
+#[cfg(feature = "cli")]
+pub mod allocations;
+#[cfg(feature = "cli")]
+pub mod answers;
+#[cfg(feature = "cli")]
+pub mod bench;
+#[cfg(feature = "cli")]
+pub mod differential;
+#[cfg(feature = "cli")]
+pub mod fetch;
+#[cfg(feature = "cli")]
+pub mod fuzz;
+#[cfg(feature = "cli")]
+pub mod generate;
+#[cfg(feature = "cli")]
+pub mod player;
+#[cfg(feature = "cli")]
+pub mod runner;
+#[cfg(feature = "cli")]
+pub mod scaffold;
+#[cfg(all(test, feature = "cli"))]
+mod stub;
+#[cfg(feature = "cli")]
+pub mod submit;
+
+pub use error::ParseError;
+pub use solution::{Answer, Params, Part, Solution, Solver};
+
+pub struct Day {
+    pub number: u8,
+    pub solver: &'static dyn Solver,
+}
+
+pub static DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        solver: &day1::Day1,
+    },
+    Day {
+        number: 2,
+        solver: &day2::Day2,
+    },
+    Day {
+        number: 3,
+        solver: &day3::Day3,
+    },
+    Day {
+        number: 4,
+        solver: &day4::Day4,
+    },
+    Day {
+        number: 5,
+        solver: &day5::Day5,
+    },
+    Day {
+        number: 6,
+        solver: &day6::Day6,
+    },
+    Day {
+        number: 8,
+        solver: &day8::Day8,
+    },
+    Day {
+        number: 9,
+        solver: &day9::Day9,
+    },
+    Day {
+        number: 10,
+        solver: &day10::Day10,
+    },
+    Day {
+        number: 11,
+        solver: &day11::Day11,
+    },
+    Day {
+        number: 12,
+        solver: &day12::Day12,
+    },
+    Day {
+        number: 13,
+        solver: &day13::Day13,
+    },
+    Day {
+        number: 14,
+        solver: &day14::Day14,
+    },
+    Day {
+        number: 15,
+        solver: &day15::Day15,
+    },
+];
+
+pub fn find_day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+#[test]
+pub fn test_find_day() {
+    let day = find_day(1).unwrap();
+    let answer = day
+        .solver
+        .run(&helper::read_string(1, false).unwrap(), Part::One)
+        .unwrap();
+    assert_eq!(answer, Answer::from(69883));
+    assert!(find_day(7).is_none());
+}
@@ -0,0 +1,847 @@
+use std::{
+    env,
+    io::{self, Write},
+    iter,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    process::ExitCode,
+    str::FromStr,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand};
+use serde::Serialize;
+
+use aoc_2022::{
+    allocations,
+    answers::{self, Answers, Status},
+    bench::{self, Report},
+    differential::{self, find_reference, REFERENCES},
+    fetch::Client,
+    find_day,
+    fuzz::{self, CRASH_DIR},
+    generate::{find_generator, Generator, Rng},
+    helper::{input_dir, panic_message, Input, INPUT_DIR_VAR},
+    player,
+    runner::{self, Task, TaskReport, TaskStatus},
+    scaffold::{self, Layout},
+    submit::{self, Submissions, Verdict, SUBMISSIONS_FILE},
+    trace::{self, TRACE_VAR},
+    visual::{self, find_simulation, Format, Recording},
+    Answer, Day, Params, Part, DAYS,
+};
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: aoc_2022::allocations::Counting = aoc_2022::allocations::Counting;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Run the Advent of Code 2022 solutions")]
+struct Cli {
+    /// Directory holding dayN.txt and dayN_test.txt, defaults to $AOC_INPUT_DIR or resources/
+    #[arg(long, global = true)]
+    input_dir: Option<PathBuf>,
+    /// Diagnostics to show on stderr, like `debug` or `info,day15=trace`, defaults to $AOC_TRACE
+    #[arg(long, global = true, value_name = "SPEC")]
+    trace: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one day, both parts unless --part is given, or every day with --all
+    Run(RunArgs),
+    /// Solve every registered day and check the answers against answers.toml
+    Verify(SuiteArgs),
+    /// Time parsing and both parts over repeated runs
+    Bench(BenchArgs),
+    /// Record the frames of a simulation day as text or PPM/PGM images
+    Record(RecordArgs),
+    /// Play a simulation day in the terminal
+    Play(PlayArgs),
+    /// Download a day's input and example into the input directory, unless already there
+    Fetch(FetchArgs),
+    /// Solve one part on the real input and submit the answer
+    Submit(SubmitArgs),
+    /// Print a random valid input for a day
+    Generate(GenerateArgs),
+    /// Time a day on generated inputs of doubling size to find where it stops scaling
+    Stress(StressArgs),
+    /// Check the optimized solvers against brute-force ones on small generated inputs
+    Diff(DiffArgs),
+    /// Feed random and mutated inputs to the parsers and save the ones that panic
+    Fuzz(FuzzArgs),
+    /// Start a new day: its module, registration in lib.rs and empty inputs
+    New(NewArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[arg(long, required_unless_present = "all")]
+    day: Option<u8>,
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    /// Solve every registered day at once, each part as its own task
+    #[arg(long, conflicts_with_all = ["day", "input"])]
+    all: bool,
+    /// Threads to solve on with --all, defaults to one per CPU
+    #[arg(long, requires = "all")]
+    jobs: Option<usize>,
+    /// Seconds each part may take with --all before it is reported as timed out
+    #[arg(long, default_value = "60", value_parser = parse_seconds, requires = "all")]
+    timeout: Duration,
+    /// table, or json for one document with every part's answer, input hash, time and status
+    #[arg(long, default_value = "table", requires = "all")]
+    format: OutputFormat,
+    #[command(flatten)]
+    input: InputArgs,
+    /// Numbers the puzzle gives outside the input, like row=10,limit=20 for day 15,
+    /// defaults to the ones answers.toml or the sidecar lists for the input
+    #[arg(long, value_name = "NAME=VALUE,...", conflicts_with = "all")]
+    params: Option<Params>,
+}
+
+#[derive(Clone, Copy)]
+enum OutputFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(anyhow!("Format must be table or json")),
+        }
+    }
+}
+
+/// What `aoc run --all --format json` prints.
+#[derive(Serialize)]
+struct RunDocument<'a> {
+    example: bool,
+    jobs: usize,
+    duration_ns: u64,
+    results: &'a [TaskReport],
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Read the puzzle input from this file, or from stdin when given -
+    #[arg(long, conflicts_with = "example")]
+    input: Option<PathBuf>,
+    /// Use the example input, dayN_test.txt
+    #[arg(long)]
+    example: bool,
+}
+
+impl InputArgs {
+    fn source(&self, day: u8) -> Input {
+        match &self.input {
+            Some(path) if path.as_os_str() == "-" => Input::Stdin,
+            Some(path) => Input::Path(path.clone()),
+            None => Input::Day {
+                day,
+                test: self.example,
+            },
+        }
+    }
+}
+
+#[derive(Args)]
+struct SuiteArgs {
+    /// Use the example inputs, dayN_test.txt
+    #[arg(long)]
+    example: bool,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    #[command(flatten)]
+    suite: SuiteArgs,
+    /// Only bench this day
+    #[arg(long)]
+    day: Option<u8>,
+    /// Times to run each day
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u16).range(1..))]
+    runs: u16,
+    /// Write the report as JSON to this file
+    #[arg(long)]
+    output: Option<PathBuf>,
+    /// Compare medians against a report written earlier with --output
+    #[arg(long)]
+    baseline: Option<PathBuf>,
+    /// Percent slowdown from the baseline that counts as a regression
+    #[arg(long, default_value_t = 10.0, requires = "baseline")]
+    threshold: f64,
+}
+
+#[derive(Args)]
+struct RecordArgs {
+    #[arg(long)]
+    day: u8,
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+    #[command(flatten)]
+    input: InputArgs,
+    /// ascii, ppm or pgm
+    #[arg(long, default_value = "ascii")]
+    format: Format,
+    /// Pixels per cell in images
+    #[arg(long, default_value_t = 4)]
+    scale: usize,
+    /// Keep only every Nth frame
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    every: u64,
+    /// Write numbered frame files to this directory, ascii frames go to stdout without it
+    #[arg(long, required_if_eq_any = [("format", "ppm"), ("format", "pgm")])]
+    out: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct PlayArgs {
+    #[arg(long)]
+    day: u8,
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+    #[command(flatten)]
+    input: InputArgs,
+    /// Frames per second to start at, + and - change it while playing
+    #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..=1000))]
+    fps: u32,
+    /// Start paused, n steps one frame at a time
+    #[arg(long)]
+    paused: bool,
+}
+
+#[derive(Args)]
+struct FetchArgs {
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=25))]
+    day: u8,
+    /// Which code block of the puzzle page is the example, counting from 1. Some
+    /// puzzles show other code first, delete a wrong dayN_test.txt and fetch again
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    block: u64,
+}
+
+#[derive(Args)]
+struct SubmitArgs {
+    #[arg(long)]
+    day: u8,
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: u8,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    #[arg(long)]
+    day: u8,
+    /// How big the input is, what it counts depends on the day
+    #[arg(long, default_value_t = 100)]
+    size: usize,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(Args)]
+struct StressArgs {
+    #[arg(long)]
+    day: u8,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Size of the first input, doubled every step
+    #[arg(long, default_value_t = 16)]
+    from: usize,
+    /// Largest size to try
+    #[arg(long, default_value_t = 1 << 16)]
+    to: usize,
+    /// Times to run each size
+    #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u16).range(1..))]
+    runs: u16,
+    /// Stop once one step of one run takes longer than this many seconds
+    #[arg(long, default_value = "5", value_parser = parse_seconds)]
+    budget: Duration,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Only check this day, instead of every day with a brute-force solver
+    #[arg(long)]
+    day: Option<u8>,
+    /// Generated inputs to try per day
+    #[arg(long, default_value_t = 200)]
+    seeds: u64,
+}
+
+#[derive(Args)]
+struct FuzzArgs {
+    /// Only fuzz this day, instead of every solved day
+    #[arg(long)]
+    day: Option<u8>,
+    /// Inputs to try per day
+    #[arg(long, default_value_t = 100_000)]
+    iterations: usize,
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(Args)]
+struct NewArgs {
+    #[arg(long)]
+    day: u8,
+    /// Create src/dayN/mod.rs instead of src/dayN.rs, for days with submodules
+    #[arg(long)]
+    dir: bool,
+}
+
+impl SuiteArgs {
+    fn source(&self, day: u8) -> Input {
+        Input::Day {
+            day,
+            test: self.example,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(dir) = &cli.input_dir {
+        env::set_var(INPUT_DIR_VAR, dir);
+    }
+    if let Some(spec) = &cli.trace {
+        env::set_var(TRACE_VAR, spec);
+    }
+    if let Err(err) = trace::init_from_env() {
+        eprintln!("error: {:#}", err);
+        return ExitCode::FAILURE;
+    }
+    let result = match cli.command {
+        Command::Run(args) => run(&args),
+        Command::Verify(args) => verify(&args),
+        Command::Bench(args) => bench(&args),
+        Command::Record(args) => record(&args),
+        Command::Play(args) => play(&args),
+        Command::Fetch(args) => fetch(&args),
+        Command::Submit(args) => submit(&args),
+        Command::Generate(args) => generate(&args),
+        Command::Stress(args) => stress(&args),
+        Command::Diff(args) => diff(&args),
+        Command::Fuzz(args) => fuzz(&args),
+        Command::New(args) => new_day(&args),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {:#}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &RunArgs) -> Result<()> {
+    let parts = match args.part {
+        Some(part) => vec![Part::try_from(part)?],
+        None => Part::ALL.to_vec(),
+    };
+    let Some(number) = args.day else {
+        return run_all(args, &parts);
+    };
+    let day = find_day(number).ok_or_else(|| anyhow!("Day {} is not solved", number))?;
+
+    let source = args.input.source(day.number);
+    let params = match &args.params {
+        Some(params) => params.clone(),
+        None => answers::params_for(&source)?,
+    };
+    let input = day.solver.parse_with(&source.load()?, &params)?;
+    for part in parts {
+        print_answer(day, part, &day.solver.solve(input.as_ref(), part)?);
+    }
+    Ok(())
+}
+
+fn run_all(args: &RunArgs, parts: &[Part]) -> Result<()> {
+    let mut tasks = Vec::new();
+    let mut reports = Vec::new();
+    for day in DAYS {
+        let source = args.input.source(day.number);
+        match source
+            .load()
+            .and_then(|input| Ok((input, answers::params_for(&source)?)))
+        {
+            Ok((input, params)) => {
+                let input: Arc<str> = Arc::from(input);
+                tasks.extend(parts.iter().map(|&part| Task {
+                    day,
+                    part,
+                    input: Arc::clone(&input),
+                    params: params.clone(),
+                }));
+            }
+            Err(err) => reports.extend(parts.iter().map(|&part| TaskReport {
+                day: day.number,
+                part,
+                status: TaskStatus::Failed(format!("{:#}", err)),
+                duration: Duration::ZERO,
+                input_hash: None,
+            })),
+        }
+    }
+
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |jobs| jobs.get()));
+    // Panics are part of the summary, not printed as they happen.
+    let start = Instant::now();
+    reports.extend(quietly(|| runner::run_parallel(tasks, jobs, args.timeout)));
+    let elapsed = start.elapsed();
+    reports.sort_by_key(|report| (report.day, u8::from(report.part)));
+    let failed = reports
+        .iter()
+        .filter(|report| !matches!(report.status, TaskStatus::Solved(_)))
+        .count();
+
+    match args.format {
+        OutputFormat::Table => print_reports(&reports, elapsed, jobs),
+        OutputFormat::Json => {
+            let document = RunDocument {
+                example: args.input.example,
+                jobs,
+                duration_ns: elapsed.as_nanos().try_into().unwrap_or(u64::MAX),
+                results: &reports,
+            };
+            println!("{}", serde_json::to_string_pretty(&document)?);
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow!("{} parts did not solve", failed));
+    }
+    Ok(())
+}
+
+fn print_reports(reports: &[TaskReport], elapsed: Duration, jobs: usize) {
+    println!(
+        "{:>3} {:>4}  {:<7} {:>12}  answer",
+        "day", "part", "status", "time"
+    );
+    for report in reports {
+        let detail = match &report.status {
+            TaskStatus::Solved(answer) => answer.to_string(),
+            TaskStatus::Failed(err) | TaskStatus::Panicked(err) => err.clone(),
+            TaskStatus::TimedOut => String::new(),
+        };
+        let mut lines = detail.lines();
+        println!(
+            "{:>3} {:>4}  {:<7} {:>12?}  {}",
+            report.day,
+            report.part,
+            report.status,
+            report.duration,
+            lines.next().unwrap_or("")
+        );
+        for line in lines {
+            println!("{:>32}{}", "", line);
+        }
+    }
+
+    let solved = reports
+        .iter()
+        .filter(|report| matches!(report.status, TaskStatus::Solved(_)))
+        .count();
+    println!(
+        "{} of {} parts solved in {:?} on {} threads",
+        solved,
+        reports.len(),
+        elapsed,
+        jobs
+    );
+}
+
+fn verify(args: &SuiteArgs) -> Result<()> {
+    let answers = Answers::load()?;
+    let (mut failed, mut missing) = (0, 0);
+    for day in DAYS {
+        match answers::verify(day, args.example, &answers) {
+            Ok(checks) => {
+                for check in checks {
+                    let detail = match &check.status {
+                        Status::Pass => String::new(),
+                        Status::Fail { expected } => {
+                            failed += 1;
+                            format!(", expected {} got {}", expected, check.answer)
+                        }
+                        Status::Missing => {
+                            missing += 1;
+                            format!(", got {}", check.answer)
+                        }
+                    };
+                    println!(
+                        "Day {} part {}: {}{}",
+                        day.number, check.part, check.status, detail
+                    );
+                }
+            }
+            Err(err) => {
+                failed += 1;
+                println!("Day {}: could not solve: {:#}", day.number, err);
+            }
+        }
+    }
+
+    println!("{} failed, {} missing", failed, missing);
+    if failed > 0 {
+        Err(anyhow!("{} checks failed", failed))
+    } else {
+        Ok(())
+    }
+}
+
+fn bench(args: &BenchArgs) -> Result<()> {
+    let days: Vec<&Day> = match args.day {
+        Some(number) => {
+            vec![find_day(number).ok_or_else(|| anyhow!("Day {} is not solved", number))?]
+        }
+        None => DAYS.iter().collect(),
+    };
+
+    let counting = allocations::is_counting();
+    print!(
+        "{:>4} {:>8} {:>12} {:>12} {:>12}",
+        "day", "step", "min", "median", "max"
+    );
+    if counting {
+        print!(" {:>10} {:>12}", "allocs", "peak bytes");
+    }
+    println!();
+    let mut report = Report {
+        runs: args.runs.into(),
+        example: args.suite.example,
+        days: Vec::new(),
+    };
+    for day in days {
+        let source = args.suite.source(day.number);
+        let params = answers::params_for(&source)?;
+        let result = bench::bench_day(day, &source.load()?, &params, report.runs)?;
+        for (step, stats) in result.steps() {
+            print!(
+                "{:>4} {:>8} {:>12?} {:>12?} {:>12?}",
+                day.number, step, stats.min, stats.median, stats.max
+            );
+            if let Some(allocations) = stats.allocations {
+                print!(" {:>10} {:>12}", allocations.count, allocations.peak_bytes);
+            }
+            println!();
+        }
+        report.days.push(result);
+    }
+
+    if let Some(path) = &args.output {
+        report.save(path)?;
+    }
+    if let Some(path) = &args.baseline {
+        let baseline = Report::load(path)?;
+        if baseline.example != report.example {
+            return Err(anyhow!(
+                "{} was not run on the same inputs, see --example",
+                path.display()
+            ));
+        }
+        let regressions = bench::compare(&report, &baseline, args.threshold);
+        for regression in &regressions {
+            println!(
+                "Day {} {}: {:?} -> {:?}, {:.0}% slower",
+                regression.day,
+                regression.step,
+                regression.baseline,
+                regression.current,
+                regression.slowdown()
+            );
+        }
+        if !regressions.is_empty() {
+            return Err(anyhow!(
+                "{} steps regressed more than {}%",
+                regressions.len(),
+                args.threshold
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn record(args: &RecordArgs) -> Result<()> {
+    let simulation = find_simulation(args.day)
+        .ok_or_else(|| anyhow!("Day {} has no simulation to record", args.day))?;
+    let input = args.input.source(args.day).load()?;
+    let mut recording = Recording::new(args.every as usize);
+    (simulation.run)(&input, Part::try_from(args.part)?, &mut recording)?;
+
+    match &args.out {
+        Some(dir) => {
+            let paths = visual::write_sequence(&recording.frames, dir, args.format, args.scale)?;
+            println!("Wrote {} frames to {}", paths.len(), dir.display());
+        }
+        None => {
+            let mut out = io::stdout().lock();
+            for frame in &recording.frames {
+                visual::write_frame(frame, Format::Ascii, 1, &mut out)?;
+                writeln!(out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn play(args: &PlayArgs) -> Result<()> {
+    let simulation = find_simulation(args.day)
+        .ok_or_else(|| anyhow!("Day {} has no simulation to play", args.day))?;
+    let input = args.input.source(args.day).load()?;
+    player::play(
+        simulation,
+        &input,
+        Part::try_from(args.part)?,
+        args.fps,
+        args.paused,
+    )
+}
+
+fn fetch(args: &FetchArgs) -> Result<()> {
+    let client = Client::from_env()?;
+    let input = client.fetch_input(args.day)?;
+    let example = client.fetch_example(args.day, args.block as usize)?;
+    for (fetched, what) in [(&input, "input"), (&example, "example")] {
+        let state = if fetched.downloaded {
+            "downloaded"
+        } else {
+            "cached"
+        };
+        println!("{}: {} {}", fetched.path.display(), what, state);
+    }
+    if example.downloaded {
+        println!(
+            "Check that code block {} of the puzzle is its example",
+            args.block
+        );
+    }
+    Ok(())
+}
+
+fn submit(args: &SubmitArgs) -> Result<()> {
+    let day = find_day(args.day).ok_or_else(|| anyhow!("Day {} is not solved", args.day))?;
+    let part = Part::try_from(args.part)?;
+    let source = Input::Day {
+        day: day.number,
+        test: false,
+    };
+    let answer = day
+        .solver
+        .run_with(&source.load()?, &answers::params_for(&source)?, part)?;
+
+    let client = Client::from_env()?;
+    let path = input_dir().join(SUBMISSIONS_FILE);
+    let mut submissions = Submissions::load(&path)?;
+    let verdict = submit::submit(&client, &mut submissions, day.number, part, &answer)?;
+    submissions.save(&path)?;
+    println!(
+        "Day {} part {}: {} is {}",
+        day.number, part, answer, verdict
+    );
+    match verdict {
+        Verdict::Correct => Ok(()),
+        _ => Err(anyhow!("The answer was not accepted")),
+    }
+}
+
+fn generator(day: u8) -> Result<&'static Generator> {
+    find_generator(day).ok_or_else(|| anyhow!("Day {} has no input generator", day))
+}
+
+fn generate(args: &GenerateArgs) -> Result<()> {
+    let generator = generator(args.day)?;
+    let params = (generator.params)(args.size);
+    if !params.is_empty() {
+        eprintln!("note: solve with --params {}", params);
+    }
+    print!(
+        "{}",
+        (generator.generate)(&mut Rng::new(args.seed), args.size)
+    );
+    Ok(())
+}
+
+fn diff(args: &DiffArgs) -> Result<()> {
+    let references =
+        match args.day {
+            Some(day) => vec![find_reference(day)
+                .ok_or_else(|| anyhow!("Day {} has no brute-force solver", day))?],
+            None => REFERENCES.iter().collect(),
+        };
+    for reference in references {
+        // Panics of the optimized solvers are reported with the mismatch.
+        if let Some(mismatch) = quietly(|| differential::find_mismatch(reference, args.seeds))? {
+            print!("{}", mismatch);
+            return Err(anyhow!(
+                "Day {} disagrees with its brute-force solver",
+                reference.day
+            ));
+        }
+        println!("day {:>2}  {} inputs agree", reference.day, args.seeds);
+    }
+    Ok(())
+}
+
+fn fuzz(args: &FuzzArgs) -> Result<()> {
+    let days = match args.day {
+        Some(day) => vec![find_day(day).ok_or_else(|| anyhow!("Day {} is not solved", day))?],
+        None => DAYS.iter().collect(),
+    };
+    if !cfg!(debug_assertions) {
+        eprintln!("note: overflow is not caught in release builds, run with --profile fuzz");
+    }
+    let mut crashed = 0;
+    for day in days {
+        // The crashes are reported below, one line each.
+        match quietly(|| fuzz::fuzz_day(day, args.seed, args.iterations)) {
+            Some(crash) => {
+                crashed += 1;
+                let path = fuzz::save_crash(&crash, &PathBuf::from(CRASH_DIR))?;
+                println!(
+                    "day {:>2}  panicked: {}, saved {}",
+                    day.number,
+                    crash.message,
+                    path.display()
+                );
+            }
+            None => println!("day {:>2}  {} inputs parsed", day.number, args.iterations),
+        }
+    }
+    if crashed > 0 {
+        return Err(anyhow!("{} parsers panicked", crashed));
+    }
+    Ok(())
+}
+
+fn new_day(args: &NewArgs) -> Result<()> {
+    let root = env::current_dir()?;
+    if !root.join("Cargo.toml").exists() {
+        return Err(anyhow!("Run aoc new from the root of the crate"));
+    }
+    let layout = if args.dir {
+        Layout::Directory
+    } else {
+        Layout::File
+    };
+    for path in scaffold::new_day(&root, &input_dir(), args.day, layout)? {
+        println!("{}", path.strip_prefix(&root).unwrap_or(&path).display());
+    }
+    Ok(())
+}
+
+fn stress(args: &StressArgs) -> Result<()> {
+    let day = find_day(args.day).ok_or_else(|| anyhow!("Day {} is not solved", args.day))?;
+    let generator = generator(args.day)?;
+    println!("size counts {}", generator.size);
+    println!(
+        "{:>8} {:>10} {:>12} {:>12} {:>12} {:>7}",
+        "size", "bytes", "parse", "part 1", "part 2", "growth"
+    );
+
+    let mut previous: Option<Duration> = None;
+    for size in doubling(args.from, args.to) {
+        let input = (generator.generate)(&mut Rng::new(args.seed), size);
+        let params = (generator.params)(size);
+        let result = bench::bench_day(day, &input, &params, args.runs.into())?;
+        let total: Duration = result.steps().iter().map(|(_, stats)| stats.median).sum();
+        let growth = match previous {
+            Some(previous) if !previous.is_zero() => {
+                format!("{:.1}x", total.as_secs_f64() / previous.as_secs_f64())
+            }
+            _ => String::new(),
+        };
+        println!(
+            "{:>8} {:>10} {:>12?} {:>12?} {:>12?} {:>7}",
+            size,
+            input.len(),
+            result.parse.median,
+            result.part1.median,
+            result.part2.median,
+            growth
+        );
+
+        let slowest = result.steps().iter().map(|(_, stats)| stats.max).max();
+        if slowest.is_some_and(|slowest| slowest > args.budget) {
+            println!("Stopped, a step took longer than {:?}", args.budget);
+            break;
+        }
+        previous = Some(total);
+    }
+    Ok(())
+}
+
+/// `from`, doubled until past `to`. Stops rather than wrap at `usize::MAX`.
+fn doubling(from: usize, to: usize) -> impl Iterator<Item = usize> {
+    iter::successors(Some(from.max(1)), |size| size.checked_mul(2))
+        .take_while(move |&size| size <= to)
+}
+
+/// Seconds as a duration, for options like `--timeout 0.5`.
+fn parse_seconds(s: &str) -> Result<Duration> {
+    let seconds: f64 = s
+        .parse()
+        .map_err(|_| anyhow!("Expected a number of seconds, got {}", s))?;
+    if seconds <= 0.0 {
+        return Err(anyhow!("Expected more than 0 seconds, got {}", s));
+    }
+    Duration::try_from_secs_f64(seconds).map_err(|_| anyhow!("{} seconds is out of range", s))
+}
+
+/// Runs `f` without printing panic messages, for commands that catch panics
+/// and report them themselves. The previous hook is back once `f` returns,
+/// and a panic `f` lets through is still printed.
+fn quietly<T>(f: impl FnOnce() -> T) -> T {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    panic::set_hook(previous);
+    result.unwrap_or_else(|payload| {
+        eprintln!("panicked: {}", panic_message(payload.as_ref()));
+        panic::resume_unwind(payload)
+    })
+}
+
+fn print_answer(day: &Day, part: Part, answer: &Answer) {
+    let answer = answer.to_string();
+    if answer.contains('\n') {
+        println!("Day {} part {}:\n{}", day.number, part, answer);
+    } else {
+        println!("Day {} part {}: {}", day.number, part, answer);
+    }
+}
+
+#[test]
+fn test_cli() {
+    use clap::CommandFactory;
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn test_parse_seconds() {
+    assert_eq!(parse_seconds("0.5").unwrap(), Duration::from_millis(500));
+    for bad in ["0", "-1", "NaN", "inf", "1e300", "soon"] {
+        assert!(parse_seconds(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn test_doubling() {
+    assert_eq!(doubling(0, 10).collect::<Vec<_>>(), vec![1, 2, 4, 8]);
+    assert_eq!(doubling(3, 24).collect::<Vec<_>>(), vec![3, 6, 12, 24]);
+    assert_eq!(doubling(usize::MAX / 2 + 1, usize::MAX).count(), 1);
+}
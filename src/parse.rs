@@ -0,0 +1,115 @@
+//! Small nom parsers shared by the days, and [`parse_all`] to run one over a
+//! whole input.
+
+use std::str::FromStr;
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, digit1, newline},
+    combinator::{cut, eof, map_res, not, opt, recognize},
+    multi::separated_list1,
+    sequence::{pair, preceded, separated_pair, terminated},
+    Parser,
+};
+
+use crate::{
+    error::{from_nom, ParseError},
+    point::Point,
+};
+
+pub type IResult<'a, T> = nom::IResult<&'a str, T>;
+
+/// Runs `parser` over `input` and fails unless it consumed everything but
+/// trailing newlines. Errors point at the line and column nom stopped at.
+pub fn parse_all<'a, T>(
+    input: &'a str,
+    mut parser: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> Result<T, ParseError> {
+    match parser.parse(input) {
+        Ok((rest, value)) if rest.trim_end_matches('\n').is_empty() => Ok(value),
+        Ok((rest, _)) => Err(ParseError::at_offset(
+            "unexpected trailing input",
+            input,
+            rest.trim_start_matches('\n'),
+        )),
+        Err(err) => Err(from_nom(input, err)),
+    }
+}
+
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<'_, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// An integer with an optional leading `-`.
+pub fn signed<T: FromStr>(input: &str) -> IResult<'_, T> {
+    map_res(recognize(pair(opt(char('-')), digit1)), str::parse)(input)
+}
+
+/// `x=-2, y=15`
+pub fn coordinates(input: &str) -> IResult<'_, Point> {
+    separated_pair(
+        preceded(tag("x="), signed),
+        tag(", "),
+        preceded(tag("y="), signed),
+    )
+    .map(|(x, y)| Point::new(x, y))
+    .parse(input)
+}
+
+/// A line break with more text after it, so trailing newlines and blank lines
+/// end a list instead of starting another item.
+fn line_break(input: &str) -> IResult<'_, char> {
+    terminated(newline, not(alt((eof, tag("\n")))))(input)
+}
+
+/// One `item` per line. An item that fails after a line break is an error
+/// at that position rather than the end of the list.
+pub fn lines<'a, T>(
+    item: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    separated_list1(line_break, cut(item))
+}
+
+/// Blocks separated by a blank line, like the monkeys of day 11. Like
+/// [`lines`], a block that fails to parse is reported where it failed.
+pub fn blocks<'a, T>(
+    block: impl Parser<&'a str, T, nom::error::Error<&'a str>>,
+) -> impl FnMut(&'a str) -> IResult<'a, Vec<T>> {
+    separated_list1(pair(newline, line_break), cut(block))
+}
+
+#[test]
+pub fn test_numbers() {
+    assert_eq!(unsigned::<u8>("42,"), Ok((",", 42)));
+    assert!(unsigned::<u8>("-1").is_err());
+    assert!(unsigned::<u8>("300").is_err());
+    assert_eq!(signed::<i32>("-17 "), Ok((" ", -17)));
+    assert_eq!(signed::<i64>("8"), Ok(("", 8)));
+}
+
+#[test]
+pub fn test_coordinates() {
+    assert_eq!(coordinates("x=-2, y=15:"), Ok((":", Point::new(-2, 15))));
+    assert!(coordinates("x=1,y=2").is_err());
+}
+
+#[test]
+pub fn test_parse_all() {
+    assert_eq!(parse_all("1\n2\n", lines(unsigned::<u8>)), Ok(vec![1, 2]));
+    assert_eq!(
+        parse_all("1\n\n2\n3\n", blocks(lines(unsigned::<u8>))),
+        Ok(vec![vec![1], vec![2, 3]])
+    );
+
+    let err = parse_all("1\n2\nthree\n", lines(unsigned::<u8>)).unwrap_err();
+    assert_eq!((err.line, err.column), (Some(3), Some(1)));
+    assert_eq!(err.text, "three");
+
+    let err = parse_all("1\n2\n\n3\n", lines(unsigned::<u8>)).unwrap_err();
+    assert_eq!((err.line, err.column), (Some(4), Some(1)));
+    assert_eq!(err.message, "unexpected trailing input");
+
+    let err = parse_all("x=1, y=z", coordinates).unwrap_err();
+    assert_eq!((err.line, err.column), (Some(1), Some(8)));
+}
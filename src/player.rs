@@ -0,0 +1,381 @@
+//! Plays a simulation in the terminal with ANSI escape codes. The player is a
+//! [`Recorder`], so the simulation only advances when the player lets it.
+
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    terminal,
+};
+
+use crate::{
+    grid::Pos,
+    solution::Part,
+    visual::{color, Frame, Recorder, Simulation},
+};
+
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+const HELP: &str = "space pause  n step  +/- speed  q quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Pause,
+    Step,
+    Faster,
+    Slower,
+    Quit,
+}
+
+impl Control {
+    pub fn from_key(key: KeyEvent) -> Option<Control> {
+        if key.kind != KeyEventKind::Press {
+            return None;
+        }
+        match key.code {
+            // Raw mode swallows the interrupt, so ^C has to quit by hand.
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                Some(Control::Quit)
+            }
+            KeyCode::Char(' ') | KeyCode::Char('p') => Some(Control::Pause),
+            KeyCode::Char('n') | KeyCode::Char('.') | KeyCode::Right => Some(Control::Step),
+            KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Up => Some(Control::Faster),
+            KeyCode::Char('-') | KeyCode::Down => Some(Control::Slower),
+            KeyCode::Char('q') | KeyCode::Esc => Some(Control::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Where the player reads its controls from.
+pub trait Controls {
+    /// The next control given within `timeout`, waiting for ever without one.
+    fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Option<Control>>;
+}
+
+/// Key presses on the terminal, which must be in raw mode.
+pub struct Keyboard;
+
+impl Controls for Keyboard {
+    fn wait(&mut self, timeout: Option<Duration>) -> io::Result<Option<Control>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let left = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            if let Some(left) = left {
+                if !event::poll(left)? {
+                    return Ok(None);
+                }
+            }
+            if let Event::Key(key) = event::read()? {
+                if let Some(control) = Control::from_key(key) {
+                    return Ok(Some(control));
+                }
+            }
+        }
+    }
+}
+
+/// Controls given up front, for tests. Once they run out every wait times out.
+impl Controls for VecDeque<Control> {
+    fn wait(&mut self, _timeout: Option<Duration>) -> io::Result<Option<Control>> {
+        Ok(self.pop_front())
+    }
+}
+
+/// The part of a frame that fits on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Viewport {
+    pub fn new(width: usize, height: usize) -> Viewport {
+        Viewport {
+            x: 0,
+            y: 0,
+            width: width.max(1),
+            height: height.max(1),
+        }
+    }
+
+    /// Scrolls just enough to keep `focus` a quarter of the view away from
+    /// the edges, without scrolling past the frame.
+    pub fn follow(&mut self, focus: Pos, frame: &Frame) {
+        self.x = follow_axis(self.x, self.width, focus.0, frame.width());
+        self.y = follow_axis(self.y, self.height, focus.1, frame.height());
+    }
+}
+
+fn follow_axis(start: usize, view: usize, focus: usize, total: usize) -> usize {
+    if total <= view {
+        return 0;
+    }
+    let margin = view / 4;
+    let start = if focus < start + margin {
+        focus.saturating_sub(margin)
+    } else if focus + margin >= start + view {
+        focus + margin + 1 - view
+    } else {
+        start
+    };
+    start.min(total - view)
+}
+
+/// Draws every frame it is given, then holds the simulation back until the
+/// next tick, or until stepped while paused.
+pub struct Player<C, W> {
+    controls: C,
+    out: W,
+    title: String,
+    viewport: Viewport,
+    delay: Duration,
+    paused: bool,
+    quit: bool,
+    frames: usize,
+    error: Option<io::Error>,
+}
+
+impl<C: Controls, W: Write> Player<C, W> {
+    /// `size` is the screen in cells, one line of it goes to the status.
+    pub fn new(controls: C, out: W, title: String, size: (usize, usize), fps: u32) -> Self {
+        Player {
+            controls,
+            out,
+            title,
+            viewport: Viewport::new(size.0, size.1.saturating_sub(1)),
+            delay: (Duration::from_secs(1) / fps.max(1)).clamp(MIN_DELAY, MAX_DELAY),
+            paused: false,
+            quit: false,
+            frames: 0,
+            error: None,
+        }
+    }
+
+    pub fn paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.viewport
+    }
+
+    /// Waits for the viewer to quit after the simulation ended, and returns
+    /// the first error drawing or reading keys ran into.
+    pub fn finish(mut self) -> io::Result<()> {
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        if !self.quit {
+            self.draw_status("done")?;
+            while !matches!(self.controls.wait(None)?, Some(Control::Quit) | None) {}
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &Frame) -> io::Result<()> {
+        let view = self.viewport;
+        let mut screen = String::from("\x1b[H");
+        for y in view.y..(view.y + view.height).min(frame.height()) {
+            let row = frame.row(y);
+            let mut current = None;
+            for &cell in &row[view.x.min(row.len())..(view.x + view.width).min(row.len())] {
+                let [r, g, b] = color(cell);
+                if current != Some([r, g, b]) {
+                    screen.push_str(&format!("\x1b[38;2;{};{};{}m", r, g, b));
+                    current = Some([r, g, b]);
+                }
+                screen.push(cell);
+            }
+            screen.push_str("\x1b[0m\x1b[K\r\n");
+        }
+        screen.push_str("\x1b[J");
+        self.out.write_all(screen.as_bytes())?;
+        let state = if self.paused { "paused" } else { "playing" };
+        self.draw_status(state)
+    }
+
+    fn draw_status(&mut self, state: &str) -> io::Result<()> {
+        let fps = 1.0 / self.delay.as_secs_f64();
+        write!(
+            self.out,
+            "\x1b[{};1H\x1b[7m {}  frame {}  {:.0} fps  {}  {} \x1b[0m\x1b[K",
+            self.viewport.height + 1,
+            self.title,
+            self.frames,
+            fps,
+            state,
+            HELP
+        )?;
+        self.out.flush()
+    }
+
+    /// Lets the simulation go on after the delay, or after a step when paused.
+    fn wait(&mut self) -> io::Result<()> {
+        let deadline = Instant::now() + self.delay;
+        loop {
+            let timeout =
+                (!self.paused).then(|| deadline.saturating_duration_since(Instant::now()));
+            match self.controls.wait(timeout)? {
+                None if self.paused => {
+                    // Scripted controls ran out while paused, nothing can
+                    // unpause any more.
+                    self.quit = true;
+                    return Ok(());
+                }
+                None => return Ok(()),
+                Some(Control::Pause) => self.paused = !self.paused,
+                Some(Control::Step) => {
+                    self.paused = true;
+                    return Ok(());
+                }
+                Some(Control::Faster) => self.delay = (self.delay / 2).max(MIN_DELAY),
+                Some(Control::Slower) => self.delay = (self.delay * 2).min(MAX_DELAY),
+                Some(Control::Quit) => {
+                    self.quit = true;
+                    return Ok(());
+                }
+            }
+            let state = if self.paused { "paused" } else { "playing" };
+            self.draw_status(state)?;
+        }
+    }
+}
+
+impl<C: Controls, W: Write> Recorder for Player<C, W> {
+    /// Nothing is wanted after quitting, the simulation runs to its end
+    /// without drawing.
+    fn wants_frame(&mut self) -> bool {
+        !self.quit
+    }
+
+    fn push(&mut self, frame: Frame, focus: Pos) {
+        self.frames += 1;
+        self.viewport.follow(focus, &frame);
+        if let Err(err) = self.draw(&frame).and_then(|_| self.wait()) {
+            self.error = Some(err);
+            self.quit = true;
+        }
+    }
+}
+
+/// Raw mode on the alternate screen, put back on drop even when the
+/// simulation panics.
+struct Terminal;
+
+impl Terminal {
+    fn enter() -> io::Result<Terminal> {
+        terminal::enable_raw_mode()?;
+        let mut out = io::stdout();
+        out.write_all(b"\x1b[?1049h\x1b[?25l\x1b[2J")?;
+        out.flush()?;
+        Ok(Terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = out.write_all(b"\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Plays `simulation` on `input` at `fps` frames a second until it ends and
+/// the viewer quits.
+pub fn play(
+    simulation: &Simulation,
+    input: &str,
+    part: Part,
+    fps: u32,
+    paused: bool,
+) -> Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let _terminal = Terminal::enter()?;
+    let title = format!("day {} part {}", simulation.day, part);
+    let mut player = Player::new(
+        Keyboard,
+        io::stdout().lock(),
+        title,
+        (columns.into(), rows.into()),
+        fps,
+    )
+    .paused(paused);
+    (simulation.run)(input, part, &mut player)?;
+    player.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+fn frame(width: usize, height: usize) -> Frame {
+    Frame::new(width, height, '.')
+}
+
+#[test]
+pub fn test_viewport_follows_focus() {
+    let frame = frame(100, 10);
+    let mut viewport = Viewport::new(20, 10);
+    viewport.follow((10, 5), &frame);
+    assert_eq!((viewport.x, viewport.y), (0, 0));
+    viewport.follow((16, 5), &frame);
+    assert_eq!(viewport.x, 2);
+    viewport.follow((99, 5), &frame);
+    assert_eq!(viewport.x, 80);
+    viewport.follow((40, 5), &frame);
+    assert_eq!(viewport.x, 35);
+    viewport.follow((0, 0), &frame);
+    assert_eq!(viewport.x, 0);
+}
+
+#[test]
+pub fn test_player_controls() {
+    use crate::visual::record;
+
+    let controls = VecDeque::from([
+        Control::Faster,
+        Control::Pause,
+        Control::Step,
+        Control::Quit,
+    ]);
+    let mut player = Player::new(controls, Vec::new(), "test".to_string(), (4, 3), 10);
+    for i in 0..5 {
+        record(&mut player, || (frame(8, 2), (i, 0)));
+    }
+    // The first frame waits out the faster tick, pauses and is stepped past,
+    // the second quits.
+    assert_eq!(player.frames, 2);
+    assert!(player.quit && player.paused);
+    assert_eq!(player.delay, Duration::from_millis(50));
+    assert_eq!(player.viewport().x, 0);
+
+    let out = String::from_utf8(player.out).unwrap();
+    assert!(out.starts_with("\x1b[H"));
+    assert!(out.contains("...."));
+    assert!(!out.contains("....."));
+    assert!(out.contains("frame 2  20 fps  paused"));
+}
+
+#[test]
+pub fn test_key_bindings() {
+    let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+    assert_eq!(
+        Control::from_key(key(KeyCode::Char(' '))),
+        Some(Control::Pause)
+    );
+    assert_eq!(Control::from_key(key(KeyCode::Right)), Some(Control::Step));
+    assert_eq!(Control::from_key(key(KeyCode::Char('x'))), None);
+    assert_eq!(
+        Control::from_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+        Some(Control::Quit)
+    );
+}
@@ -0,0 +1,243 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::grid::Pos;
+
+/// A position or offset on the plane. `y` grows downwards, like rows in a
+/// grid, so [`Direction::Up`] is `(0, -1)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point::new(0, 0);
+
+    /// Offsets to the eight surrounding points, clockwise from up.
+    pub const AROUND: [Point; 8] = [
+        Point::new(0, -1),
+        Point::new(1, -1),
+        Point::new(1, 0),
+        Point::new(1, 1),
+        Point::new(0, 1),
+        Point::new(-1, 1),
+        Point::new(-1, 0),
+        Point::new(-1, -1),
+    ];
+
+    pub const fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    /// Overflows for points more than `i32::MAX` apart, see
+    /// [`Point::checked_manhattan`] for untrusted points.
+    pub fn manhattan(self, other: Point) -> i32 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    pub fn checked_manhattan(self, other: Point) -> Option<i32> {
+        let dx = self.x.checked_sub(other.x)?.checked_abs()?;
+        let dy = self.y.checked_sub(other.y)?.checked_abs()?;
+        dx.checked_add(dy)
+    }
+
+    /// Distance in king moves, diagonal steps count as one.
+    pub fn chebyshev(self, other: Point) -> i32 {
+        (self.x - other.x).abs().max((self.y - other.y).abs())
+    }
+
+    /// Each coordinate clamped to -1, 0 or 1, a single step towards the offset.
+    pub fn signum(self) -> Point {
+        Point::new(self.x.signum(), self.y.signum())
+    }
+
+    /// A quarter turn clockwise around the origin, as seen on screen.
+    pub fn rotate_right(self) -> Point {
+        Point::new(-self.y, self.x)
+    }
+
+    /// A quarter turn counter-clockwise around the origin, as seen on screen.
+    pub fn rotate_left(self) -> Point {
+        Point::new(self.y, -self.x)
+    }
+
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        Direction::ALL
+            .into_iter()
+            .map(move |direction| self + direction.offset())
+    }
+
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        Point::AROUND.into_iter().map(move |offset| self + offset)
+    }
+
+    /// Inside the rectangle from `lower` to `upper`, both inclusive.
+    pub fn within(self, lower: Point, upper: Point) -> bool {
+        (lower.x..=upper.x).contains(&self.x) && (lower.y..=upper.y).contains(&self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<i32> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i32) -> Point {
+        Point::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Point {
+        Point::new(x, y)
+    }
+}
+
+impl From<Point> for (i32, i32) {
+    fn from(point: Point) -> (i32, i32) {
+        (point.x, point.y)
+    }
+}
+
+impl TryFrom<Pos> for Point {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from((x, y): Pos) -> Result<Point, Self::Error> {
+        Ok(Point::new(x.try_into()?, y.try_into()?))
+    }
+}
+
+/// Fails for points left of or above the origin.
+impl TryFrom<Point> for Pos {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(point: Point) -> Result<Pos, Self::Error> {
+        Ok((point.x.try_into()?, point.y.try_into()?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+
+    pub fn turn_right(self) -> Direction {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Direction {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+}
+
+#[test]
+pub fn test_distances() {
+    let a = Point::new(1, 2);
+    let b = Point::new(-3, 5);
+    assert_eq!(a.manhattan(b), 7);
+    assert_eq!(a.chebyshev(b), 4);
+    assert_eq!(a.manhattan(a), 0);
+    assert_eq!(a.checked_manhattan(b), Some(7));
+    let far = Point::new(2_000_000_000, 0);
+    assert_eq!(far.checked_manhattan(-far), None);
+}
+
+#[test]
+pub fn test_ops() {
+    let mut point = Point::new(1, 2) + Point::new(3, -1) * 2;
+    assert_eq!(point, Point::new(7, 0));
+    point -= Point::new(7, 1);
+    assert_eq!(point, Point::new(0, -1));
+    assert_eq!(-point, Point::new(0, 1));
+    assert_eq!(Point::new(-5, 3).signum(), Point::new(-1, 1));
+}
+
+#[test]
+pub fn test_rotation() {
+    let right = Direction::Right.offset();
+    assert_eq!(right.rotate_right(), Direction::Down.offset());
+    assert_eq!(right.rotate_left(), Direction::Up.offset());
+    for direction in Direction::ALL {
+        assert_eq!(
+            direction.offset().rotate_right(),
+            direction.turn_right().offset()
+        );
+        assert_eq!(direction.turn_left().turn_right(), direction);
+        assert_eq!(direction.opposite().offset(), -direction.offset());
+    }
+}
+
+#[test]
+pub fn test_conversions() {
+    assert_eq!(Point::try_from((3usize, 4usize)), Ok(Point::new(3, 4)));
+    assert_eq!(Pos::try_from(Point::new(3, 4)), Ok((3, 4)));
+    assert!(Pos::try_from(Point::new(-1, 4)).is_err());
+    assert_eq!(<(i32, i32)>::from(Point::from((1, -1))), (1, -1));
+}
+
+#[test]
+pub fn test_neighbours() {
+    let point = Point::new(2, 2);
+    assert!(point.neighbours4().all(|other| point.manhattan(other) == 1));
+    assert!(point.neighbours8().all(|other| point.chebyshev(other) == 1));
+    assert_eq!(point.neighbours8().count(), 8);
+}
@@ -0,0 +1,322 @@
+//! Solves many days and parts at once on a pool of worker threads. A part
+//! that panics or runs past its timeout is reported without holding up the
+//! others.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::{
+    helper::{input_hash, panic_message},
+    solution::{Answer, Params, Part},
+    Day,
+};
+
+/// One part of one day to solve on `input`, parsed with `params`.
+#[derive(Clone)]
+pub struct Task {
+    pub day: &'static Day,
+    pub part: Part,
+    pub input: Arc<str>,
+    pub params: Params,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskStatus {
+    Solved(Answer),
+    Failed(String),
+    Panicked(String),
+    TimedOut,
+}
+
+impl fmt::Display for TaskStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskStatus::Solved(_) => f.pad("ok"),
+            TaskStatus::Failed(_) => f.pad("error"),
+            TaskStatus::Panicked(_) => f.pad("panic"),
+            TaskStatus::TimedOut => f.pad("timeout"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskReport {
+    pub day: u8,
+    pub part: Part,
+    pub status: TaskStatus,
+    /// Parsing and solving, or the timeout when the task timed out.
+    pub duration: Duration,
+    /// See [`input_hash`], unset when the input could not be read.
+    pub input_hash: Option<String>,
+}
+
+/// Flat, with the answer or error next to the status and the duration in
+/// nanoseconds.
+impl Serialize for TaskReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let (answer, error) = match &self.status {
+            TaskStatus::Solved(answer) => (Some(answer), None),
+            TaskStatus::Failed(err) | TaskStatus::Panicked(err) => (None, Some(err)),
+            TaskStatus::TimedOut => (None, None),
+        };
+        let mut report = serializer.serialize_struct("TaskReport", 7)?;
+        report.serialize_field("day", &self.day)?;
+        report.serialize_field("part", &u8::from(self.part))?;
+        report.serialize_field("status", &self.status.to_string())?;
+        report.serialize_field("answer", &answer)?;
+        report.serialize_field("error", &error)?;
+        report.serialize_field("input_hash", &self.input_hash)?;
+        report.serialize_field(
+            "duration_ns",
+            &u64::try_from(self.duration.as_nanos()).unwrap_or(u64::MAX),
+        )?;
+        report.end()
+    }
+}
+
+type Queue = Arc<Mutex<VecDeque<(usize, Task)>>>;
+
+enum Event {
+    /// With the worker's flag, set once the task times out.
+    Started(usize, Instant, Arc<AtomicBool>),
+    Finished(usize, TaskStatus, Duration),
+}
+
+/// Runs `tasks` on `jobs` threads and reports them in the order given.
+///
+/// A thread cannot be stopped from outside, so a task that times out is left
+/// running on its own and a new worker takes its place. The abandoned worker
+/// exits once the task returns, so no more than `jobs` workers take tasks.
+pub fn run_parallel(tasks: Vec<Task>, jobs: usize, timeout: Duration) -> Vec<TaskReport> {
+    let mut reports: Vec<TaskReport> = tasks
+        .iter()
+        .map(|task| TaskReport {
+            day: task.day.number,
+            part: task.part,
+            status: TaskStatus::TimedOut,
+            duration: timeout,
+            input_hash: Some(input_hash(&task.input)),
+        })
+        .collect();
+    let queue: Queue = Arc::new(Mutex::new(tasks.into_iter().enumerate().collect()));
+    let (events, received) = mpsc::channel();
+    for _ in 0..jobs.clamp(1, reports.len().max(1)) {
+        spawn_worker(Arc::clone(&queue), events.clone());
+    }
+
+    let mut running: HashMap<usize, (Instant, Arc<AtomicBool>)> = HashMap::new();
+    let mut left = reports.len();
+    while left > 0 {
+        let event = match running.values().map(|&(at, _)| at).min() {
+            Some(first) => {
+                received.recv_timeout((first + timeout).saturating_duration_since(Instant::now()))
+            }
+            None => received.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        match event {
+            Ok(Event::Started(index, at, abandoned)) => {
+                running.insert(index, (at, abandoned));
+            }
+            Ok(Event::Finished(index, status, duration)) => {
+                // Late results of timed out tasks are dropped.
+                if running.remove(&index).is_some() {
+                    reports[index].status = status;
+                    reports[index].duration = duration;
+                    left -= 1;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                let now = Instant::now();
+                running.retain(|_, (at, abandoned)| {
+                    let expired = *at + timeout <= now;
+                    if expired {
+                        abandoned.store(true, Ordering::Relaxed);
+                        left -= 1;
+                        spawn_worker(Arc::clone(&queue), events.clone());
+                    }
+                    !expired
+                });
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+    reports
+}
+
+fn spawn_worker(queue: Queue, events: Sender<Event>) {
+    let abandoned = Arc::new(AtomicBool::new(false));
+    thread::spawn(move || loop {
+        if abandoned.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some((index, task)) = queue.lock().unwrap().pop_front() else {
+            return;
+        };
+        let start = Instant::now();
+        if events
+            .send(Event::Started(index, start, Arc::clone(&abandoned)))
+            .is_err()
+        {
+            return;
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            task.day
+                .solver
+                .run_with(&task.input, &task.params, task.part)
+        }));
+        let status = match result {
+            Ok(Ok(answer)) => TaskStatus::Solved(answer),
+            Ok(Err(err)) => TaskStatus::Failed(format!("{:#}", err)),
+            Err(payload) => TaskStatus::Panicked(panic_message(payload.as_ref())),
+        };
+        if events
+            .send(Event::Finished(index, status, start.elapsed()))
+            .is_err()
+        {
+            return;
+        }
+    });
+}
+
+/// Answers part 1 with the input length, after acting out the input.
+#[cfg(test)]
+struct Scripted;
+
+/// Tasks in `count` now and the most there were at once.
+#[cfg(test)]
+static COUNTING: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+#[cfg(test)]
+static MOST_COUNTING: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+#[cfg(test)]
+impl crate::solution::Solution for Scripted {
+    type Input = String;
+
+    fn parse(input: &str) -> anyhow::Result<String> {
+        match input {
+            "bad" => Err(anyhow::anyhow!("bad input")),
+            "panic" => panic!("scripted panic"),
+            "hang" => {
+                thread::sleep(Duration::from_secs(5));
+                Ok(input.to_string())
+            }
+            "nap" => {
+                thread::sleep(Duration::from_millis(300));
+                Ok(input.to_string())
+            }
+            "count" => {
+                let now = COUNTING.fetch_add(1, Ordering::SeqCst) + 1;
+                MOST_COUNTING.fetch_max(now, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(50));
+                COUNTING.fetch_sub(1, Ordering::SeqCst);
+                Ok(input.to_string())
+            }
+            _ => Ok(input.to_string()),
+        }
+    }
+
+    fn part1(input: &String) -> anyhow::Result<Answer> {
+        Ok(Answer::from(input.len()))
+    }
+
+    fn part2(input: &String) -> anyhow::Result<Answer> {
+        Ok(Answer::from(input.as_str()))
+    }
+}
+
+#[test]
+pub fn test_run_parallel() {
+    static SCRIPTED: Day = Day {
+        number: 99,
+        solver: &Scripted,
+    };
+    let task = |part, input: &str| Task {
+        day: &SCRIPTED,
+        part,
+        input: Arc::from(input),
+        params: Params::new(),
+    };
+    let tasks = vec![
+        task(Part::One, "hang"),
+        task(Part::One, "panic"),
+        task(Part::One, "abc"),
+        task(Part::Two, "bad"),
+        task(Part::Two, "xyz"),
+    ];
+
+    let start = Instant::now();
+    let reports = run_parallel(tasks, 2, Duration::from_millis(300));
+    assert!(start.elapsed() < Duration::from_secs(3));
+    let statuses: Vec<TaskStatus> = reports.into_iter().map(|report| report.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            TaskStatus::TimedOut,
+            TaskStatus::Panicked("scripted panic".to_string()),
+            TaskStatus::Solved(Answer::from(3)),
+            TaskStatus::Failed("bad input".to_string()),
+            TaskStatus::Solved(Answer::from("xyz")),
+        ]
+    );
+}
+
+#[test]
+pub fn test_abandoned_worker_exits() {
+    static SCRIPTED: Day = Day {
+        number: 99,
+        solver: &Scripted,
+    };
+    let mut tasks = vec![Task {
+        day: &SCRIPTED,
+        part: Part::One,
+        input: Arc::from("nap"),
+        params: Params::new(),
+    }];
+    for _ in 0..10 {
+        tasks.push(Task {
+            day: &SCRIPTED,
+            part: Part::One,
+            input: Arc::from("count"),
+            params: Params::new(),
+        });
+    }
+
+    // The nap times out and its worker wakes up while the counts still queue.
+    let reports = run_parallel(tasks, 1, Duration::from_millis(100));
+    assert_eq!(reports[0].status, TaskStatus::TimedOut);
+    assert!(reports[1..]
+        .iter()
+        .all(|report| report.status == TaskStatus::Solved(Answer::from(5))));
+    assert_eq!(MOST_COUNTING.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+pub fn test_report_json() {
+    let report = |status| TaskReport {
+        day: 3,
+        part: Part::Two,
+        status,
+        duration: Duration::from_micros(5),
+        input_hash: Some("00ff".to_string()),
+    };
+    let json = |status| serde_json::to_string(&report(status)).unwrap();
+    assert_eq!(
+        json(TaskStatus::Solved(Answer::from(42))),
+        r#"{"day":3,"part":2,"status":"ok","answer":42,"error":null,"input_hash":"00ff","duration_ns":5000}"#
+    );
+    assert!(json(TaskStatus::Solved(Answer::from("ABC"))).contains(r#""answer":"ABC""#));
+    assert!(json(TaskStatus::Panicked("boom".to_string()))
+        .contains(r#""status":"panic","answer":null,"error":"boom""#));
+}
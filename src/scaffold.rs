@@ -0,0 +1,329 @@
+//! Starts a new day: the module with a stubbed [`Solution`], its place in
+//! `lib.rs` and the example tests, and empty input files.
+//!
+//! [`Solution`]: crate::solution::Solution
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::helper::input_name;
+
+/// Where the module of a new day goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// `src/dayN.rs`
+    File,
+    /// `src/dayN/mod.rs`, for days that grow submodules like day11.
+    Directory,
+}
+
+/// Creates day `day` in the crate at `root`, with its inputs in `input_dir`,
+/// and returns every file created or changed. Inputs that already exist are
+/// kept.
+pub fn new_day(root: &Path, input_dir: &Path, day: u8, layout: Layout) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(anyhow!("Day must be between 1 and 25, got {}", day));
+    }
+    let src = root.join("src");
+    let lib_path = src.join("lib.rs");
+    let answers_path = src.join("answers.rs");
+    let lib = read(&lib_path)?;
+    let answers = read(&answers_path)?;
+
+    let module = format!("day{}", day);
+    for existing in [src.join(format!("{}.rs", module)), src.join(&module)] {
+        if existing.exists() {
+            return Err(anyhow!("{} already exists", existing.display()));
+        }
+    }
+    let lib = register_module(&lib, day)?;
+    let answers = register_example_tests(&answers, day)?;
+
+    let module_path = match layout {
+        Layout::File => src.join(format!("{}.rs", module)),
+        Layout::Directory => src.join(&module).join("mod.rs"),
+    };
+    let mut changed = vec![module_path.clone(), lib_path.clone(), answers_path.clone()];
+    if let Some(dir) = module_path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Could not create {}", dir.display()))?;
+    }
+    write(&module_path, &module_source(day))?;
+    write(&lib_path, &lib)?;
+    write(&answers_path, &answers)?;
+
+    fs::create_dir_all(input_dir)
+        .with_context(|| format!("Could not create {}", input_dir.display()))?;
+    let sidecar = format!(
+        "# Official example answers for {}.txt\n",
+        input_name(day, true)
+    );
+    for (name, text) in [
+        (format!("{}.txt", input_name(day, false)), String::new()),
+        (format!("{}.txt", input_name(day, true)), String::new()),
+        (format!("{}.toml", input_name(day, true)), sidecar),
+    ] {
+        let path = input_dir.join(name);
+        if !path.exists() {
+            write(&path, &text)?;
+            changed.push(path);
+        }
+    }
+    Ok(changed)
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))
+}
+
+fn write(path: &Path, text: &str) -> Result<()> {
+    fs::write(path, text).with_context(|| format!("Could not write {}", path.display()))
+}
+
+/// Adds `pub mod dayN;` to the sorted module list and the day to `DAYS`.
+fn register_module(lib: &str, day: u8) -> Result<String> {
+    let module = format!("day{}", day);
+    let mut lines: Vec<String> = lib.lines().map(String::from).collect();
+
+    let module_name = |line: &str| {
+        line.trim_start_matches("pub ")
+            .strip_prefix("mod ")?
+            .strip_suffix(';')
+            .map(String::from)
+    };
+    if lines
+        .iter()
+        .any(|line| module_name(line).as_deref() == Some(module.as_str()))
+    {
+        return Err(anyhow!("{} is already a module in lib.rs", module));
+    }
+    let modules: Vec<usize> = (0..lines.len())
+        .filter(|&i| module_name(&lines[i]).is_some())
+        .collect();
+    let (first, last) = match (modules.first(), modules.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return Err(anyhow!("lib.rs has no module list")),
+    };
+    let mut at = (first..=last)
+        .find(|&i| module_name(&lines[i]).is_some_and(|name| name > module))
+        .unwrap_or(last + 1);
+    // Keep attributes like `#[cfg(test)]` on the module they belong to.
+    while at > 0 && lines[at - 1].starts_with("#[") {
+        at -= 1;
+    }
+    lines.insert(at, format!("pub mod {};", module));
+
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("pub static DAYS"))
+        .ok_or_else(|| anyhow!("lib.rs has no DAYS registry"))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line == "];")
+            .ok_or_else(|| anyhow!("The DAYS registry in lib.rs is not closed"))?;
+    // Entries are four lines, `Day {`, number, solver and `},`.
+    let at = (start..end)
+        .find(|&i| {
+            lines[i]
+                .trim()
+                .strip_prefix("number: ")
+                .and_then(|number| number.trim_end_matches(',').parse::<u8>().ok())
+                .is_some_and(|number| number > day)
+        })
+        .map_or(end, |number_line| number_line - 1);
+    let entry = [
+        "    Day {".to_string(),
+        format!("        number: {},", day),
+        format!("        solver: &{}::Day{},", module, day),
+        "    },".to_string(),
+    ];
+    lines.splice(at..at, entry);
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Adds `dayN: N,` to the `example_tests!` list, which is ordered by day.
+fn register_example_tests(answers: &str, day: u8) -> Result<String> {
+    let mut lines: Vec<String> = answers.lines().map(String::from).collect();
+    let start = lines
+        .iter()
+        .position(|line| line == "example_tests! {")
+        .ok_or_else(|| anyhow!("answers.rs has no example_tests! list"))?;
+    let end = start
+        + lines[start..]
+            .iter()
+            .position(|line| line == "}")
+            .ok_or_else(|| anyhow!("The example_tests! list in answers.rs is not closed"))?;
+    let at = (start + 1..end)
+        .find(|&i| {
+            lines[i]
+                .trim()
+                .split_once(": ")
+                .and_then(|(_, number)| number.trim_end_matches(',').parse::<u8>().ok())
+                .is_some_and(|number| number > day)
+        })
+        .unwrap_or(end);
+    lines.insert(at, format!("    day{}: {},", day, day));
+    Ok(lines.join("\n") + "\n")
+}
+
+/// A solution whose parse fails until the day is solved, so the parts can
+/// never be reached. Its tests fail until the answers are pinned in
+/// `answers.toml`.
+fn module_source(day: u8) -> String {
+    format!(
+        r#"use std::convert::Infallible;
+
+use anyhow::{{anyhow, Result}};
+
+use crate::solution::{{solution_api, Answer, Solution}};
+#[cfg(test)]
+use crate::{{
+    answers::Answers,
+    helper::read_string,
+    solution::{{Part, Solver}},
+}};
+
+pub struct Day{day};
+
+impl Solution for Day{day} {{
+    type Input = Infallible;
+
+    fn parse(_input: &str) -> Result<Self::Input> {{
+        Err(anyhow!("day {day} is not solved yet"))
+    }}
+
+    fn part1(input: &Self::Input) -> Result<Answer> {{
+        match *input {{}}
+    }}
+
+    fn part2(input: &Self::Input) -> Result<Answer> {{
+        match *input {{}}
+    }}
+}}
+
+solution_api!(Day{day});
+
+#[test]
+pub fn test_day{day}_pt_1() {{
+    let answer = Day{day}
+        .run(&read_string({day}, false).unwrap(), Part::One)
+        .unwrap();
+    let expected = Answers::load().unwrap();
+    assert_eq!(Some(&answer), expected.get("day{day}", Part::One));
+}}
+#[test]
+pub fn test_day{day}_pt_2() {{
+    let answer = Day{day}
+        .run(&read_string({day}, false).unwrap(), Part::Two)
+        .unwrap();
+    let expected = Answers::load().unwrap();
+    assert_eq!(Some(&answer), expected.get("day{day}", Part::Two));
+}}
+"#,
+        day = day
+    )
+}
+
+#[cfg(test)]
+const LIB: &str = "pub mod answers;
+mod day1;
+mod day10;
+pub mod day12;
+mod day2;
+pub mod grid;
+mod solution;
+#[cfg(test)]
+mod stub;
+
+pub static DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        solver: &day1::Day1,
+    },
+    Day {
+        number: 2,
+        solver: &day2::Day2,
+    },
+    Day {
+        number: 10,
+        solver: &day10::Day10,
+    },
+    Day {
+        number: 12,
+        solver: &day12::Day12,
+    },
+];
+";
+
+#[test]
+pub fn test_register_module() {
+    let lib = register_module(LIB, 7).unwrap();
+    assert!(lib.contains("mod day2;\npub mod day7;\npub mod grid;"));
+    assert!(lib.contains(
+        "        solver: &day2::Day2,\n    },\n    Day {\n        number: 7,\n        solver: &day7::Day7,\n    },\n    Day {\n        number: 10,"
+    ));
+
+    let lib = register_module(LIB, 11).unwrap();
+    assert!(lib.contains("mod day10;\npub mod day11;\npub mod day12;"));
+    assert!(lib.contains("        solver: &day11::Day11,\n    },\n    Day {\n        number: 12,"));
+
+    let lib = register_module(LIB, 20).unwrap();
+    assert!(lib.contains("mod day2;\npub mod day20;\npub mod grid;"));
+    assert!(lib.contains("        solver: &day20::Day20,\n    },\n];"));
+
+    assert!(register_module(LIB, 12).is_err());
+    let lib = register_module(&LIB.replace("pub mod grid;\n", ""), 20).unwrap();
+    assert!(lib.contains("mod day2;\npub mod day20;\nmod solution;"));
+}
+
+#[test]
+pub fn test_register_example_tests() {
+    let answers = "fn x() {}\n\nexample_tests! {\n    day1: 1,\n    day10: 10,\n}\n";
+    assert_eq!(
+        register_example_tests(answers, 7).unwrap(),
+        "fn x() {}\n\nexample_tests! {\n    day1: 1,\n    day7: 7,\n    day10: 10,\n}\n"
+    );
+    assert!(register_example_tests(answers, 25)
+        .unwrap()
+        .ends_with("    day10: 10,\n    day25: 25,\n}\n"));
+}
+
+#[test]
+pub fn test_new_day() {
+    let root = crate::stub::temp_dir("scaffold");
+    let inputs = root.join("resources");
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(&inputs).unwrap();
+    fs::write(root.join("src/lib.rs"), LIB).unwrap();
+    fs::write(
+        root.join("src/answers.rs"),
+        "example_tests! {\n    day1: 1,\n}\n",
+    )
+    .unwrap();
+    fs::write(inputs.join("day7_test.txt"), "$ ls\n").unwrap();
+
+    let changed = new_day(&root, &inputs, 7, Layout::Directory).unwrap();
+    assert_eq!(changed.len(), 5);
+    let module = fs::read_to_string(root.join("src/day7/mod.rs")).unwrap();
+    assert!(module.contains("impl Solution for Day7 {"));
+    assert!(module.contains("solution_api!(Day7);"));
+    assert!(!module.contains("todo!"));
+    assert!(module.contains("Err(anyhow!(\"day 7 is not solved yet\"))"));
+    assert!(module.contains("pub fn test_day7_pt_2() {"));
+    assert_eq!(fs::read_to_string(inputs.join("day7.txt")).unwrap(), "");
+    assert_eq!(
+        fs::read_to_string(inputs.join("day7_test.txt")).unwrap(),
+        "$ ls\n"
+    );
+    assert!(fs::read_to_string(root.join("src/answers.rs"))
+        .unwrap()
+        .contains("    day7: 7,"));
+
+    assert!(new_day(&root, &inputs, 7, Layout::File).is_err());
+    assert!(new_day(&root, &inputs, 26, Layout::File).is_err());
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,217 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// The cheapest way to a goal: its total cost and every state from the start
+/// the search began at to the goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S, C> {
+    pub cost: C,
+    pub path: Vec<S>,
+}
+
+impl<S, C> Found<S, C> {
+    pub fn goal(&self) -> &S {
+        self.path.last().expect("a path holds at least the start")
+    }
+}
+
+/// States seen so far, each with the state it was first reached from.
+struct Visited<S> {
+    index: HashMap<S, usize>,
+    states: Vec<(S, Option<usize>)>,
+}
+
+impl<S: Clone + Eq + Hash> Visited<S> {
+    fn new() -> Visited<S> {
+        Visited {
+            index: HashMap::new(),
+            states: Vec::new(),
+        }
+    }
+
+    /// The id of `state`, and whether this is the first time it was seen.
+    fn insert(&mut self, state: S, parent: Option<usize>) -> (usize, bool) {
+        match self.index.entry(state) {
+            Entry::Occupied(entry) => (*entry.get(), false),
+            Entry::Vacant(entry) => {
+                let id = self.states.len();
+                self.states.push((entry.key().clone(), parent));
+                entry.insert(id);
+                (id, true)
+            }
+        }
+    }
+
+    fn path(&self, mut id: usize) -> Vec<S> {
+        let mut path = vec![self.states[id].0.clone()];
+        while let Some(parent) = self.states[id].1 {
+            path.push(self.states[parent].0.clone());
+            id = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth-first search where every step costs one. Searching from several
+/// starts at once finds the goal closest to any of them.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = Visited::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if let (id, true) = visited.insert(start, None) {
+            queue.push_back((id, 0));
+        }
+    }
+
+    while let Some((id, cost)) = queue.pop_front() {
+        let state = visited.states[id].0.clone();
+        if goal(&state) {
+            return Some(Found {
+                cost,
+                path: visited.path(id),
+            });
+        }
+        for next in neighbours(&state) {
+            if let (next, true) = visited.insert(next, Some(id)) {
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Cheapest path search for non-negative step costs.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbours: impl FnMut(&S) -> I,
+    goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, neighbours, |_| C::default(), goal)
+}
+
+/// [`dijkstra`] guided by `heuristic`, an estimate of the remaining cost to a
+/// goal. It must be consistent, never dropping by more than a step costs.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut visited = Visited::new();
+    let mut costs = Vec::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if let (id, true) = visited.insert(start.clone(), None) {
+            costs.push(C::default());
+            heap.push(Reverse((heuristic(&start), id)));
+        }
+    }
+
+    let mut done = vec![false; costs.len()];
+    while let Some(Reverse((_, id))) = heap.pop() {
+        if std::mem::replace(&mut done[id], true) {
+            continue;
+        }
+        let state = visited.states[id].0.clone();
+        if goal(&state) {
+            return Some(Found {
+                cost: costs[id],
+                path: visited.path(id),
+            });
+        }
+        for (next, step) in neighbours(&state) {
+            let cost = costs[id] + step;
+            let estimate = cost + heuristic(&next);
+            let (next, new) = visited.insert(next, Some(id));
+            if new {
+                costs.push(cost);
+                done.push(false);
+            } else if cost < costs[next] && !done[next] {
+                costs[next] = cost;
+                visited.states[next].1 = Some(id);
+            } else {
+                continue;
+            }
+            heap.push(Reverse((estimate, next)));
+        }
+    }
+    None
+}
+
+/// A 5x5 open field with a wall down column 2 that has a gap at the bottom.
+#[cfg(test)]
+fn field_neighbours(&(x, y): &(i32, i32)) -> Vec<(i32, i32)> {
+    [(0, 1), (1, 0), (0, -1), (-1, 0)]
+        .into_iter()
+        .map(|(dx, dy)| (x + dx, y + dy))
+        .filter(|&(x, y)| (0..5).contains(&x) && (0..5).contains(&y) && (x != 2 || y == 4))
+        .collect()
+}
+
+#[test]
+pub fn test_bfs() {
+    let found = bfs([(0, 0)], field_neighbours, |&pos| pos == (4, 0)).unwrap();
+    assert_eq!(found.cost, 12);
+    assert_eq!(found.path.len(), 13);
+    assert_eq!(found.path[0], (0, 0));
+    assert_eq!(*found.goal(), (4, 0));
+    assert!(found
+        .path
+        .windows(2)
+        .all(|step| field_neighbours(&step[0]).contains(&step[1])));
+
+    assert_eq!(bfs([(0, 0)], field_neighbours, |&pos| pos == (9, 9)), None);
+}
+
+#[test]
+pub fn test_multi_source() {
+    let found = bfs([(0, 0), (3, 0)], field_neighbours, |&pos| pos == (4, 0)).unwrap();
+    assert_eq!(found.cost, 1);
+    assert_eq!(found.path, vec![(3, 0), (4, 0)]);
+}
+
+#[test]
+pub fn test_dijkstra_and_astar() {
+    // Moving right costs 5, every other step 1.
+    let weighted = |pos: &(i32, i32)| {
+        let pos = *pos;
+        field_neighbours(&pos)
+            .into_iter()
+            .map(move |next| (next, if next.0 > pos.0 { 5 } else { 1 }))
+    };
+    let goal = |pos: &(i32, i32)| *pos == (4, 4);
+
+    let found = dijkstra([(0, 0)], weighted, goal).unwrap();
+    assert_eq!(found.cost, 4 + 4 * 5);
+    let heuristic = |&(x, y): &(i32, i32)| (4 - x) * 5 + (4 - y);
+    assert_eq!(
+        astar([(0, 0)], weighted, heuristic, goal).unwrap().cost,
+        found.cost
+    );
+
+    let found = dijkstra([(0, 0)], weighted, |pos| *pos == (0, 0)).unwrap();
+    assert_eq!((found.cost, found.path), (0, vec![(0, 0)]));
+}
@@ -0,0 +1,307 @@
+use std::{any::Any, collections::BTreeMap, convert::Infallible, fmt, str::FromStr};
+
+use anyhow::{anyhow, Result};
+use serde::{Serialize, Serializer};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Numbers as JSON numbers, text as strings.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Number(value) => serializer.serialize_i64(*value),
+            Answer::Text(value) => serializer.serialize_str(value),
+        }
+    }
+}
+
+macro_rules! number_answer {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for Answer {
+            fn from(value: $ty) -> Self {
+                Answer::Number(value.into())
+            }
+        })*
+    };
+}
+
+number_answer!(u32, i32, i64);
+
+/// Numbers past `i64::MAX` are kept as their digits rather than wrapped, the
+/// same way [`FromStr`] reads them back.
+macro_rules! wide_answer {
+    ($($ty:ty),*) => {
+        $(impl From<$ty> for Answer {
+            fn from(value: $ty) -> Self {
+                i64::try_from(value)
+                    .map(Answer::Number)
+                    .unwrap_or_else(|_| Answer::Text(value.to_string()))
+            }
+        })*
+    };
+}
+
+wide_answer!(u64, u128, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+
+/// Reads an answer back from its displayed form, numbers before text.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.parse::<i64>()
+            .map(Answer::Number)
+            .unwrap_or_else(|_| Answer::from(s)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => f.pad("1"),
+            Part::Two => f.pad("2"),
+        }
+    }
+}
+
+impl From<Part> for u8 {
+    fn from(part: Part) -> u8 {
+        match part {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl TryFrom<u8> for Part {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        match value {
+            1 => Ok(Part::One),
+            2 => Ok(Part::Two),
+            _ => Err(anyhow!("Part must be 1 or 2, got {}", value)),
+        }
+    }
+}
+
+/// Numbers a puzzle gives in its text rather than in the input, by name, like
+/// the row day 15 asks about. Examples use smaller ones than the real puzzle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    values: BTreeMap<String, i64>,
+}
+
+impl Params {
+    pub fn new() -> Params {
+        Params::default()
+    }
+
+    pub fn with(mut self, name: &str, value: i64) -> Params {
+        self.values.insert(name.to_owned(), value);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Fails on names not in `known`, so a misspelled param is not ignored.
+    pub fn check(&self, known: &[&str]) -> Result<()> {
+        match self
+            .values
+            .keys()
+            .find(|name| !known.contains(&name.as_str()))
+        {
+            Some(name) => Err(anyhow!("Unknown param {}", name)),
+            None => Ok(()),
+        }
+    }
+
+    /// The value of `name`, or `default` when it is not given.
+    pub fn get_or<T: TryFrom<i64>>(&self, name: &str, default: T) -> Result<T> {
+        match self.values.get(name) {
+            Some(&value) => {
+                T::try_from(value).map_err(|_| anyhow!("Param {}={} is out of range", name, value))
+            }
+            None => Ok(default),
+        }
+    }
+}
+
+/// `row=10,limit=20`
+impl fmt::Display for Params {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.values.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Params {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.split(',')
+            .filter(|pair| !pair.trim().is_empty())
+            .try_fold(Params::new(), |params, pair| {
+                let (name, value) = pair
+                    .split_once('=')
+                    .ok_or_else(|| anyhow!("Expected name=value, got {}", pair))?;
+                let value = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| anyhow!("Expected a number for {}, got {}", name, value))?;
+                Ok(params.with(name.trim(), value))
+            })
+    }
+}
+
+/// A day's puzzle: parse the input once, then answer both parts from it. The
+/// parts fail on inputs that have no answer, like a map without a path.
+pub trait Solution {
+    type Input: 'static;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    /// Parses with `params` in place of the puzzle's own. Days that take none
+    /// reject any.
+    fn parse_with(input: &str, params: &Params) -> Result<Self::Input> {
+        params.check(&[])?;
+        Self::parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<Answer>;
+    fn part2(input: &Self::Input) -> Result<Answer>;
+}
+
+/// Object safe view of a [`Solution`] so days can be stored in the registry.
+pub trait Solver: Sync {
+    fn parse_with(&self, input: &str, params: &Params) -> Result<Box<dyn Any>>;
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer>;
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        self.parse_with(input, &Params::new())
+    }
+
+    fn run(&self, input: &str, part: Part) -> Result<Answer> {
+        self.run_with(input, &Params::new(), part)
+    }
+
+    fn run_with(&self, input: &str, params: &Params, part: Part) -> Result<Answer> {
+        let parsed = self.parse_with(input, params)?;
+        self.solve(parsed.as_ref(), part)
+    }
+}
+
+impl<S: Solution + Sync> Solver for S {
+    fn parse_with(&self, input: &str, params: &Params) -> Result<Box<dyn Any>> {
+        Ok(Box::new(S::parse_with(input, params)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answer> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input was parsed by a different solution");
+        match part {
+            Part::One => S::part1(input),
+            Part::Two => S::part2(input),
+        }
+    }
+}
+
+/// Gives a day module free `parse`, `parse_with`, `part1` and `part2`
+/// functions and its `Input` type, so other crates can call
+/// `day1::part1(&day1::parse(text)?)?` without importing [`Solution`].
+macro_rules! solution_api {
+    ($day:ident) => {
+        pub type Input = <$day as $crate::solution::Solution>::Input;
+
+        pub fn parse(input: &str) -> ::anyhow::Result<Input> {
+            <$day as $crate::solution::Solution>::parse(input)
+        }
+
+        pub fn parse_with(
+            input: &str,
+            params: &$crate::solution::Params,
+        ) -> ::anyhow::Result<Input> {
+            <$day as $crate::solution::Solution>::parse_with(input, params)
+        }
+
+        pub fn part1(input: &Input) -> ::anyhow::Result<$crate::solution::Answer> {
+            <$day as $crate::solution::Solution>::part1(input)
+        }
+
+        pub fn part2(input: &Input) -> ::anyhow::Result<$crate::solution::Answer> {
+            <$day as $crate::solution::Solution>::part2(input)
+        }
+    };
+}
+
+pub(crate) use solution_api;
+
+#[test]
+pub fn test_wide_answer() {
+    assert_eq!(Answer::from(i64::MAX as u64), Answer::Number(i64::MAX));
+    let big = Answer::from(u64::MAX);
+    assert_eq!(big.to_string(), u64::MAX.to_string());
+    assert_eq!(big.to_string().parse::<Answer>().unwrap(), big);
+    assert_eq!(Answer::from(usize::MAX).to_string(), usize::MAX.to_string());
+    assert_eq!(Answer::from(u128::MAX).to_string(), u128::MAX.to_string());
+}
+
+#[test]
+pub fn test_params() {
+    let params: Params = "row=10, limit=20".parse().unwrap();
+    assert_eq!(params, Params::new().with("limit", 20).with("row", 10));
+    assert_eq!(params.to_string(), "limit=20,row=10");
+    assert_eq!(params.get_or("row", 0_i32).unwrap(), 10);
+    assert_eq!(params.get_or("column", 3_i32).unwrap(), 3);
+    assert!(params.check(&["row", "limit"]).is_ok());
+    assert!(params.check(&["row"]).is_err());
+    assert!(Params::new()
+        .with("row", 1 << 40)
+        .get_or("row", 0_i32)
+        .is_err());
+    assert!("row".parse::<Params>().is_err());
+    assert!("row=ten".parse::<Params>().is_err());
+    assert_eq!("".parse::<Params>().unwrap(), Params::new());
+}
@@ -0,0 +1,94 @@
+//! A local HTTP server standing in for adventofcode.com in tests.
+
+use std::{
+    sync::{Arc, Mutex},
+    thread::{self, JoinHandle},
+};
+
+use tiny_http::{Header, Response, Server};
+
+/// A request the stub received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Received {
+    pub method: String,
+    pub path: String,
+    pub cookie: Option<String>,
+    pub body: String,
+}
+
+type Handler = dyn Fn(&Received) -> (u16, String) + Send + Sync;
+
+/// Answers every request with `handler` until dropped.
+pub struct Stub {
+    pub url: String,
+    server: Arc<Server>,
+    received: Arc<Mutex<Vec<Received>>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Stub {
+    pub fn serve(handler: impl Fn(&Received) -> (u16, String) + Send + Sync + 'static) -> Stub {
+        let server = Arc::new(Server::http("127.0.0.1:0").unwrap());
+        let url = format!("http://{}", server.server_addr().to_ip().unwrap());
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let handler: Box<Handler> = Box::new(handler);
+
+        let thread = {
+            let (server, received) = (Arc::clone(&server), Arc::clone(&received));
+            thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let mut body = String::new();
+                    request.as_reader().read_to_string(&mut body).unwrap();
+                    let cookie = request
+                        .headers()
+                        .iter()
+                        .find(|header| header.field.equiv("Cookie"))
+                        .map(|header| header.value.to_string());
+                    let call = Received {
+                        method: request.method().to_string(),
+                        path: request.url().to_string(),
+                        cookie,
+                        body,
+                    };
+                    let (status, body) = handler(&call);
+                    received.lock().unwrap().push(call);
+                    let response = Response::from_string(body)
+                        .with_status_code(status)
+                        .with_header(
+                            Header::from_bytes("Content-Type", "text/html; charset=utf-8").unwrap(),
+                        );
+                    let _ = request.respond(response);
+                }
+            })
+        };
+
+        Stub {
+            url,
+            server,
+            received,
+            thread: Some(thread),
+        }
+    }
+
+    /// Every request so far, oldest first.
+    pub fn received(&self) -> Vec<Received> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+impl Drop for Stub {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// A fresh, empty directory under the system temp directory.
+pub fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("aoc-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,358 @@
+//! Posts answers to adventofcode.com and remembers the ones it rejected, so a
+//! known wrong answer is never sent twice.
+
+use std::{collections::BTreeMap, fmt, fs, path::Path, time::Duration};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    fetch::{text_of, Client, YEAR},
+    solution::{Answer, Part},
+};
+
+/// Submissions are kept next to the inputs, in JSON so the answers registry
+/// does not take them for a sidecar.
+pub const SUBMISSIONS_FILE: &str = "submissions.json";
+
+/// What the site said about a submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong without a hint, as for text answers.
+    Wrong,
+    RateLimited {
+        wait: Option<Duration>,
+    },
+    /// The part was solved already, or part 1 is not solved yet.
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Reads the verdict from the `<article>` of the answer page.
+    pub fn parse(page: &str) -> Result<Verdict> {
+        let start = page.find("<article>").map_or(0, |start| start + 9);
+        let end = page[start..]
+            .find("</article>")
+            .map_or(page.len(), |end| start + end);
+        let text = text_of(&page[start..end]);
+
+        if text.contains("That's the right answer") {
+            Ok(Verdict::Correct)
+        } else if text.contains("That's not the right answer") {
+            Ok(if text.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if text.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Wrong
+            })
+        } else if text.contains("You gave an answer too recently") {
+            Ok(Verdict::RateLimited {
+                wait: parse_wait(&text),
+            })
+        } else if text.contains("You don't seem to be solving the right level") {
+            Ok(Verdict::WrongLevel)
+        } else {
+            Err(anyhow!(
+                "Could not understand the answer page: {}",
+                text.trim()
+            ))
+        }
+    }
+}
+
+/// `You have 4m 37s left to wait.`
+fn parse_wait(text: &str) -> Option<Duration> {
+    let start = text.find("You have ")? + 9;
+    let end = start + text[start..].find(" left to wait")?;
+    text[start..end]
+        .split_whitespace()
+        .map(|amount| {
+            let (value, unit) = amount.split_at(amount.len().checked_sub(1)?);
+            let seconds = match unit {
+                "h" => 3600,
+                "m" => 60,
+                "s" => 1,
+                _ => return None,
+            };
+            Some(value.parse::<u64>().ok()? * seconds)
+        })
+        .sum::<Option<u64>>()
+        .map(Duration::from_secs)
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited, wait {}s", wait.as_secs())
+            }
+            Verdict::RateLimited { wait: None } => write!(f, "rate limited"),
+            Verdict::WrongLevel => write!(f, "already solved, or part 1 is not solved yet"),
+        }
+    }
+}
+
+/// Everything learned from submitting one part.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Log {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub correct: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<String>,
+    /// The smallest answer that was too high.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub too_high: Option<i64>,
+    /// The largest answer that was too low.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub too_low: Option<i64>,
+}
+
+impl Log {
+    /// Fails with the reason when `answer` is known to be wrong, or the part
+    /// is solved already.
+    pub fn check(&self, answer: &Answer) -> Result<()> {
+        let text = answer.to_string();
+        if let Some(correct) = &self.correct {
+            return Err(if *correct == text {
+                anyhow!("{} was already accepted", text)
+            } else {
+                anyhow!("Already solved with {}, {} must be wrong", correct, text)
+            });
+        }
+        if self.rejected.contains(&text) {
+            return Err(anyhow!("{} was already rejected", text));
+        }
+        if let Answer::Number(value) = answer {
+            if let Some(high) = self.too_high.filter(|&high| *value >= high) {
+                return Err(anyhow!(
+                    "{} is not below {}, which was too high",
+                    value,
+                    high
+                ));
+            }
+            if let Some(low) = self.too_low.filter(|&low| *value <= low) {
+                return Err(anyhow!("{} is not above {}, which was too low", value, low));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn record(&mut self, answer: &Answer, verdict: &Verdict) {
+        let text = answer.to_string();
+        match (verdict, answer) {
+            (Verdict::Correct, _) => self.correct = Some(text),
+            (Verdict::TooHigh, Answer::Number(value)) => {
+                self.too_high = Some(self.too_high.map_or(*value, |high| high.min(*value)));
+                self.rejected.push(text);
+            }
+            (Verdict::TooLow, Answer::Number(value)) => {
+                self.too_low = Some(self.too_low.map_or(*value, |low| low.max(*value)));
+                self.rejected.push(text);
+            }
+            (Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong, _) => self.rejected.push(text),
+            (Verdict::RateLimited { .. } | Verdict::WrongLevel, _) => {}
+        }
+    }
+}
+
+/// The [`Log`] of every part submitted so far, by day.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submissions {
+    days: BTreeMap<u8, [Log; 2]>,
+}
+
+impl Submissions {
+    /// An empty history when `path` does not exist yet.
+    pub fn load(path: &Path) -> Result<Submissions> {
+        if !path.exists() {
+            return Ok(Submissions::default());
+        }
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("Could not parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)? + "\n")
+            .with_context(|| format!("Could not write {}", path.display()))
+    }
+
+    pub fn log(&self, day: u8, part: Part) -> Log {
+        self.days
+            .get(&day)
+            .map(|logs| logs[part_index(part)].clone())
+            .unwrap_or_default()
+    }
+
+    fn log_mut(&mut self, day: u8, part: Part) -> &mut Log {
+        &mut self.days.entry(day).or_default()[part_index(part)]
+    }
+}
+
+fn part_index(part: Part) -> usize {
+    match part {
+        Part::One => 0,
+        Part::Two => 1,
+    }
+}
+
+impl Client {
+    /// Posts `answer` without looking at what was submitted before, see
+    /// [`submit`] for that.
+    pub fn post_answer(&self, day: u8, part: Part, answer: &Answer) -> Result<Verdict> {
+        let page = self.post(
+            &format!("/{}/day/{}/answer", YEAR, day),
+            &[
+                ("level", &part.to_string()),
+                ("answer", &answer.to_string()),
+            ],
+        )?;
+        Verdict::parse(&page)
+    }
+}
+
+/// Submits `answer` unless `submissions` already rules it out, and records
+/// the verdict in `submissions`.
+pub fn submit(
+    client: &Client,
+    submissions: &mut Submissions,
+    day: u8,
+    part: Part,
+    answer: &Answer,
+) -> Result<Verdict> {
+    if answer.to_string().contains('\n') {
+        return Err(anyhow!(
+            "The answer is a picture, read the letters off it and submit them by hand"
+        ));
+    }
+    submissions.log(day, part).check(answer)?;
+    let verdict = client.post_answer(day, part, answer)?;
+    submissions.log_mut(day, part).record(answer, &verdict);
+    Ok(verdict)
+}
+
+#[cfg(test)]
+fn fixture(name: &str) -> String {
+    fs::read_to_string(format!("resources/fixtures/answer_{}.html", name)).unwrap()
+}
+
+#[test]
+pub fn test_parse_verdict() {
+    assert_eq!(
+        Verdict::parse(&fixture("correct")).unwrap(),
+        Verdict::Correct
+    );
+    assert_eq!(
+        Verdict::parse(&fixture("too_high")).unwrap(),
+        Verdict::TooHigh
+    );
+    assert_eq!(
+        Verdict::parse(&fixture("too_low")).unwrap(),
+        Verdict::TooLow
+    );
+    assert_eq!(Verdict::parse(&fixture("wrong")).unwrap(), Verdict::Wrong);
+    assert_eq!(
+        Verdict::parse(&fixture("rate_limited")).unwrap(),
+        Verdict::RateLimited {
+            wait: Some(Duration::from_secs(4 * 60 + 37))
+        }
+    );
+    assert_eq!(
+        Verdict::parse(&fixture("already_solved")).unwrap(),
+        Verdict::WrongLevel
+    );
+    assert!(Verdict::parse("<article><p>Something new</p></article>").is_err());
+}
+
+#[test]
+pub fn test_log_bounds() {
+    let mut log = Log::default();
+    log.record(&Answer::from(100), &Verdict::TooHigh);
+    log.record(&Answer::from(150), &Verdict::TooHigh);
+    log.record(&Answer::from(40), &Verdict::TooLow);
+    log.record(&Answer::from(77), &Verdict::RateLimited { wait: None });
+    assert_eq!((log.too_low, log.too_high), (Some(40), Some(100)));
+    assert_eq!(log.rejected, vec!["100", "150", "40"]);
+
+    assert!(log.check(&Answer::from(70)).is_ok());
+    assert!(log.check(&Answer::from(100)).is_err());
+    assert!(log.check(&Answer::from(120)).is_err());
+    assert!(log.check(&Answer::from(40)).is_err());
+    assert!(log.check(&Answer::from(12)).is_err());
+
+    log.record(&Answer::from("ABC"), &Verdict::Wrong);
+    assert!(log.check(&Answer::from("ABC")).is_err());
+    log.record(&Answer::from(70), &Verdict::Correct);
+    assert!(log.check(&Answer::from(71)).is_err());
+}
+
+#[test]
+pub fn test_submit_against_stub() {
+    use crate::stub::{temp_dir, Stub};
+
+    // Too high until the answer is 24000, the real part 1 answer of the example.
+    let stub = Stub::serve(|received| match received.body.as_str() {
+        "level=1&answer=24000" => (200, fixture("correct")),
+        body if body.starts_with("level=1&") => (200, fixture("too_high")),
+        _ => (200, fixture("already_solved")),
+    });
+    let dir = temp_dir("submit");
+    let path = dir.join(SUBMISSIONS_FILE);
+    let client = Client::new("secret").base_url(&stub.url);
+
+    let mut submissions = Submissions::load(&path).unwrap();
+    let verdict = submit(
+        &client,
+        &mut submissions,
+        1,
+        Part::One,
+        &Answer::from(30000),
+    );
+    assert_eq!(verdict.unwrap(), Verdict::TooHigh);
+    submissions.save(&path).unwrap();
+
+    let mut submissions = Submissions::load(&path).unwrap();
+    assert!(submit(
+        &client,
+        &mut submissions,
+        1,
+        Part::One,
+        &Answer::from(30001)
+    )
+    .is_err());
+    let verdict = submit(
+        &client,
+        &mut submissions,
+        1,
+        Part::One,
+        &Answer::from(24000),
+    );
+    assert_eq!(verdict.unwrap(), Verdict::Correct);
+    assert_eq!(
+        submissions.log(1, Part::One).correct.as_deref(),
+        Some("24000")
+    );
+    assert!(submit(
+        &client,
+        &mut submissions,
+        1,
+        Part::Two,
+        &Answer::from("A\nB")
+    )
+    .is_err());
+
+    let received = stub.received();
+    assert_eq!(received.len(), 2);
+    assert_eq!(received[0].method, "POST");
+    assert_eq!(received[0].path, "/2022/day/1/answer");
+    assert_eq!(received[0].cookie.as_deref(), Some("session=secret"));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,213 @@
+//! Diagnostics the days keep in the code, off unless asked for. `AOC_TRACE`
+//! or `aoc --trace` pick what to show: `debug` for every day, `day13=trace`
+//! for one, or both as in `info,day15=trace`. Events go to stderr.
+//!
+//! Days emit them with `event!`, which costs a single check when tracing is
+//! off:
+//!
+//! ```ignore
+//! event!(Debug, "match found", point = sensor_match);
+//! ```
+
+use std::{
+    env, fmt,
+    io::{self, Write},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+use anyhow::{anyhow, Result};
+
+pub const TRACE_VAR: &str = "AOC_TRACE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "error" => Ok(Level::Error),
+            "warn" => Ok(Level::Warn),
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(anyhow!(
+                "Level must be off, error, warn, info, debug or trace, got {}",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        })
+    }
+}
+
+/// The most detailed level shown, per target and for the rest. `None` is off.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    default: Option<Level>,
+    targets: Vec<(String, Option<Level>)>,
+}
+
+impl Filter {
+    /// Comma separated `level`, `target=level` or a bare `target` for every
+    /// level of it. Later entries win.
+    pub fn parse(spec: &str) -> Result<Filter> {
+        let level = |level: &str| match level.trim() {
+            "off" => Ok(None),
+            level => level.parse().map(Some),
+        };
+        let mut filter = Filter::default();
+        for entry in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            match entry.split_once('=') {
+                Some((target, max)) => filter
+                    .targets
+                    .push((target.trim().to_string(), level(max)?)),
+                None => match level(entry) {
+                    Ok(max) => filter.default = max,
+                    Err(_) if entry.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => {
+                        filter.targets.push((entry.to_string(), Some(Level::Trace)))
+                    }
+                    Err(err) => return Err(err),
+                },
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn is_off(&self) -> bool {
+        self.default.is_none() && self.targets.iter().all(|(_, max)| max.is_none())
+    }
+
+    pub fn enabled(&self, target: &str, level: Level) -> bool {
+        let max = self
+            .targets
+            .iter()
+            .rev()
+            .find(|(name, _)| name == target)
+            .map_or(self.default, |&(_, max)| max);
+        max.is_some_and(|max| level <= max)
+    }
+}
+
+static FILTER: OnceLock<Filter> = OnceLock::new();
+
+/// Reads the filter from `AOC_TRACE`, failing on a spec that does not parse.
+/// Without this the first event reads it and ignores a bad spec.
+pub fn init_from_env() -> Result<()> {
+    let filter = match env::var(TRACE_VAR) {
+        Ok(spec) => Filter::parse(&spec).map_err(|err| anyhow!("{}: {:#}", TRACE_VAR, err))?,
+        Err(_) => Filter::default(),
+    };
+    let _ = FILTER.set(filter);
+    Ok(())
+}
+
+fn filter() -> &'static Filter {
+    FILTER.get_or_init(|| {
+        env::var(TRACE_VAR)
+            .ok()
+            .and_then(|spec| Filter::parse(&spec).ok())
+            .unwrap_or_default()
+    })
+}
+
+/// The day an event belongs to: `day15` for `aoc_2022::day15::part2`.
+pub fn target(module_path: &str) -> &str {
+    module_path.split("::").nth(1).unwrap_or(module_path)
+}
+
+pub fn enabled(module_path: &str, level: Level) -> bool {
+    let filter = filter();
+    !filter.is_off() && filter.enabled(target(module_path), level)
+}
+
+/// `DEBUG day15: match found point=Point { x: 14, y: 11 }`
+pub fn format_event(
+    module_path: &str,
+    level: Level,
+    message: &str,
+    fields: &[(&str, &dyn fmt::Debug)],
+) -> String {
+    let mut line = format!("{:<5} {}: {}", level, target(module_path), message);
+    for (key, value) in fields {
+        line.push_str(&format!(" {}={:?}", key, value));
+    }
+    line
+}
+
+/// Writes an event to stderr, `event!` checks [`enabled`] first.
+pub fn emit(module_path: &str, level: Level, message: &str, fields: &[(&str, &dyn fmt::Debug)]) {
+    let line = format_event(module_path, level, message, fields);
+    let _ = writeln!(io::stderr().lock(), "{}", line);
+}
+
+/// `event!(Level, "message", key = value, ...)` with every value shown by
+/// its `Debug` form. Values are only evaluated when the event is enabled.
+macro_rules! event {
+    ($level:ident, $message:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        if $crate::trace::enabled(module_path!(), $crate::trace::Level::$level) {
+            $crate::trace::emit(
+                module_path!(),
+                $crate::trace::Level::$level,
+                $message,
+                &[$((stringify!($key), &$value as &dyn ::std::fmt::Debug)),*],
+            );
+        }
+    };
+}
+
+pub(crate) use event;
+
+#[test]
+pub fn test_filter() {
+    let filter = Filter::parse("info, day15=trace,day13=off").unwrap();
+    assert!(filter.enabled("day1", Level::Info));
+    assert!(!filter.enabled("day1", Level::Debug));
+    assert!(filter.enabled("day15", Level::Trace));
+    assert!(!filter.enabled("day13", Level::Error));
+
+    let filter = Filter::parse("day9").unwrap();
+    assert!(filter.enabled("day9", Level::Trace));
+    assert!(!filter.enabled("day10", Level::Error));
+    assert!(!filter.is_off());
+
+    assert!(Filter::parse("").unwrap().is_off());
+    assert!(Filter::parse("debug,off").unwrap().is_off());
+    assert!(Filter::parse("day9=loud").is_err());
+    assert!(Filter::parse("day-9").is_err());
+}
+
+#[test]
+pub fn test_format_event() {
+    assert_eq!(target("aoc_2022::day15::part2"), "day15");
+    assert_eq!(target("aoc_2022"), "aoc_2022");
+    let line = format_event(
+        "aoc_2022::day10",
+        Level::Trace,
+        "cycle",
+        &[("cycle", &3), ("register", &-1)],
+    );
+    assert_eq!(line, "TRACE day10: cycle cycle=3 register=-1");
+}
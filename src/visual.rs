@@ -0,0 +1,293 @@
+//! Frames recorded from the simulation days, and writers that turn them into
+//! text or PPM/PGM images for looking at a run afterwards.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    grid::{Grid, Pos},
+    solution::Part,
+};
+
+/// One picture of a simulation, a char per cell.
+pub type Frame = Grid<char>;
+
+/// Where a simulation sends its frames.
+pub trait Recorder {
+    /// Whether the frame about to be drawn is wanted. Simulations ask before
+    /// rendering so a run that records nothing pays nothing.
+    fn wants_frame(&mut self) -> bool;
+
+    /// `focus` is the cell where the action is, for players that follow it.
+    fn push(&mut self, frame: Frame, focus: Pos);
+}
+
+/// Renders and pushes a frame and its focus if `recorder` wants one.
+pub fn record(recorder: &mut dyn Recorder, render: impl FnOnce() -> (Frame, Pos)) {
+    if recorder.wants_frame() {
+        let (frame, focus) = render();
+        recorder.push(frame, focus);
+    }
+}
+
+/// Records nothing, for solving without visualizing.
+impl Recorder for () {
+    fn wants_frame(&mut self) -> bool {
+        false
+    }
+
+    fn push(&mut self, _frame: Frame, _focus: Pos) {}
+}
+
+/// Keeps every `every`th frame in memory.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    pub frames: Vec<Frame>,
+    every: usize,
+    offered: usize,
+}
+
+impl Recording {
+    pub fn new(every: usize) -> Recording {
+        Recording {
+            frames: Vec::new(),
+            every: every.max(1),
+            offered: 0,
+        }
+    }
+}
+
+impl Recorder for Recording {
+    fn wants_frame(&mut self) -> bool {
+        self.offered += 1;
+        (self.offered - 1).is_multiple_of(self.every)
+    }
+
+    fn push(&mut self, frame: Frame, _focus: Pos) {
+        self.frames.push(frame);
+    }
+}
+
+/// A day that can replay its solution as frames.
+pub struct Simulation {
+    pub day: u8,
+    pub run: fn(&str, Part, &mut dyn Recorder) -> Result<()>,
+}
+
+pub static SIMULATIONS: &[Simulation] = &[
+    Simulation {
+        day: 9,
+        run: crate::day9::simulate,
+    },
+    Simulation {
+        day: 10,
+        run: crate::day10::simulate,
+    },
+    Simulation {
+        day: 12,
+        run: crate::day12::simulate,
+    },
+    Simulation {
+        day: 14,
+        run: crate::day14::simulate,
+    },
+];
+
+pub fn find_simulation(day: u8) -> Option<&'static Simulation> {
+    SIMULATIONS.iter().find(|simulation| simulation.day == day)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Ascii,
+    Ppm,
+    Pgm,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ascii => "txt",
+            Format::Ppm => "ppm",
+            Format::Pgm => "pgm",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ascii" => Ok(Format::Ascii),
+            "ppm" => Ok(Format::Ppm),
+            "pgm" => Ok(Format::Pgm),
+            _ => Err(anyhow!("Format must be ascii, ppm or pgm")),
+        }
+    }
+}
+
+/// The colour a cell is drawn with. Digits and letters shade from dark to
+/// light so elevations and knot numbers stay readable.
+pub fn color(cell: char) -> [u8; 3] {
+    match cell {
+        '.' | ' ' => [16, 16, 24],
+        '#' => [230, 230, 230],
+        'o' => [220, 180, 80],
+        '+' | 'H' => [230, 60, 60],
+        's' | 'S' => [60, 200, 80],
+        'E' => [80, 120, 240],
+        '0'..='9' => shade(cell as u8 - b'0', 9, [90, 20, 20], [250, 140, 120]),
+        'a'..='z' => shade(cell as u8 - b'a', 25, [20, 70, 30], [200, 230, 170]),
+        _ => [128, 128, 128],
+    }
+}
+
+fn shade(step: u8, steps: u8, from: [u8; 3], to: [u8; 3]) -> [u8; 3] {
+    let mix = |from: u8, to: u8| {
+        (from as u32 + (to as u32 - from as u32) * step as u32 / steps as u32) as u8
+    };
+    [
+        mix(from[0], to[0]),
+        mix(from[1], to[1]),
+        mix(from[2], to[2]),
+    ]
+}
+
+pub fn gray(cell: char) -> u8 {
+    let [r, g, b] = color(cell);
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
+}
+
+/// Writes `frame` as text, or as an image with each cell `scale` pixels wide.
+pub fn write_frame(
+    frame: &Frame,
+    format: Format,
+    scale: usize,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    let scale = scale.max(1);
+    let (width, height) = (frame.width() * scale, frame.height() * scale);
+    match format {
+        Format::Ascii => return writeln!(out, "{}", frame),
+        Format::Ppm => write!(out, "P6\n{} {}\n255\n", width, height)?,
+        Format::Pgm => write!(out, "P5\n{} {}\n255\n", width, height)?,
+    }
+
+    let mut row = Vec::new();
+    for y in 0..frame.height() {
+        row.clear();
+        for &cell in frame.row(y) {
+            for _ in 0..scale {
+                match format {
+                    Format::Ppm => row.extend(color(cell)),
+                    _ => row.push(gray(cell)),
+                }
+            }
+        }
+        for _ in 0..scale {
+            out.write_all(&row)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes one numbered file per frame into `dir`, `frame_00000.ppm` onwards.
+pub fn write_sequence(
+    frames: &[Frame],
+    dir: &Path,
+    format: Format,
+    scale: usize,
+) -> Result<Vec<PathBuf>> {
+    fs::create_dir_all(dir).with_context(|| format!("Could not create {}", dir.display()))?;
+    frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let path = dir.join(format!("frame_{:05}.{}", i, format.extension()));
+            let mut file = io::BufWriter::new(
+                fs::File::create(&path)
+                    .with_context(|| format!("Could not create {}", path.display()))?,
+            );
+            write_frame(frame, format, scale, &mut file)
+                .and_then(|_| file.flush())
+                .with_context(|| format!("Could not write {}", path.display()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+fn frame(text: &str) -> Frame {
+    Grid::parse(text, "", Some).unwrap()
+}
+
+#[test]
+pub fn test_recording() {
+    let mut recording = Recording::new(2);
+    for i in 0..5 {
+        record(&mut recording, || (frame(&i.to_string()), (0, 0)));
+    }
+    let kept: Vec<String> = recording.frames.iter().map(Frame::to_string).collect();
+    assert_eq!(kept, vec!["0", "2", "4"]);
+
+    let mut rendered = false;
+    record(&mut (), || {
+        rendered = true;
+        (frame("."), (0, 0))
+    });
+    assert!(!rendered);
+}
+
+#[test]
+pub fn test_write_frame() {
+    let frame = frame("#.\n.#\n");
+    let mut ascii = Vec::new();
+    write_frame(&frame, Format::Ascii, 1, &mut ascii).unwrap();
+    assert_eq!(String::from_utf8(ascii).unwrap(), "#.\n.#\n");
+
+    let mut ppm = Vec::new();
+    write_frame(&frame, Format::Ppm, 2, &mut ppm).unwrap();
+    let header = b"P6\n4 4\n255\n";
+    assert!(ppm.starts_with(header));
+    assert_eq!(ppm.len(), header.len() + 4 * 4 * 3);
+    assert_eq!(ppm[header.len()..header.len() + 3], color('#'));
+
+    let mut pgm = Vec::new();
+    write_frame(&frame, Format::Pgm, 1, &mut pgm).unwrap();
+    assert_eq!(
+        &pgm[pgm.len() - 4..],
+        &[gray('#'), gray('.'), gray('.'), gray('#')]
+    );
+}
+
+#[test]
+pub fn test_write_sequence() {
+    let dir = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+    let frames = [frame("#"), frame(".")];
+    let paths = write_sequence(&frames, &dir, Format::Pgm, 1).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert!(paths[1].ends_with("frame_00001.pgm"));
+    assert!(fs::read(&paths[0]).unwrap().starts_with(b"P5\n1 1\n255\n"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+pub fn test_simulations_record_frames() {
+    for simulation in SIMULATIONS {
+        let input = crate::helper::read_string(simulation.day, true).unwrap();
+        let mut recording = Recording::new(1);
+        (simulation.run)(&input, Part::One, &mut recording).unwrap();
+        assert!(
+            !recording.frames.is_empty(),
+            "day {} recorded no frames",
+            simulation.day
+        );
+    }
+}